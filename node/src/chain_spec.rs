@@ -1,7 +1,7 @@
 use sp_core::{Pair, Public, sr25519};
 use nft_swap_runtime::{
 	AccountId, AuraConfig, BalancesConfig, GenesisConfig, GrandpaConfig,
-	SudoConfig, SystemConfig, WASM_BINARY, Signature
+	SudoConfig, SystemConfig, WASM_BINARY, Signature, ExistentialDeposit, NftFeeAccount, NftDividendAccount,
 };
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
 use sp_finality_grandpa::AuthorityId as GrandpaId;
@@ -141,7 +141,13 @@ fn testnet_genesis(
 		}),
 		pallet_balances: Some(BalancesConfig {
 			// Configure endowed accounts with initial balance of 1 << 60.
-			balances: endowed_accounts.iter().cloned().map(|k|(k, 1 << 60)).collect(),
+			balances: endowed_accounts.iter().cloned().map(|k|(k, 1 << 60))
+				// The NFT fee and dividend accounts must exist to receive funds moved out of reserved balances.
+				.chain(vec![
+					(NftFeeAccount::get(), ExistentialDeposit::get()),
+					(NftDividendAccount::get(), ExistentialDeposit::get()),
+				])
+				.collect(),
 		}),
 		pallet_aura: Some(AuraConfig {
 			authorities: initial_authorities.iter().map(|x| (x.0.clone())).collect(),
@@ -0,0 +1,347 @@
+//! RPC interface for the nft pallet.
+
+use std::sync::Arc;
+
+use codec::Codec;
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+
+pub use pallet_nft_runtime_api::NftApi as NftRuntimeApi;
+use pallet_nft::{Order, OrderDetail, OrderStatus, SellParams};
+use sp_runtime::DispatchError;
+
+/// Nft RPC methods.
+#[rpc]
+pub trait NftApi<BlockHash, AccountId, NftId, OrderId, Balance, BlockNumber> {
+	/// Projected profit of every voter of an order if `who` staked `amount` for `keep_block_num` blocks.
+	#[rpc(name = "nft_simulateShares")]
+	fn simulate_shares(
+		&self,
+		order_id: OrderId,
+		who: AccountId,
+		amount: Balance,
+		keep_block_num: BlockNumber,
+		at: Option<BlockHash>,
+	) -> Result<Vec<(AccountId, u128)>>;
+
+	/// Up to `limit` orders starting at `start_key` (inclusive), plus the key of the next page.
+	#[rpc(name = "nft_ordersPaged")]
+	fn orders_paged(
+		&self,
+		start_key: Option<OrderId>,
+		limit: u32,
+		at: Option<BlockHash>,
+	) -> Result<(Vec<(OrderId, Order<OrderId, NftId, AccountId, Balance, BlockNumber>)>, Option<OrderId>)>;
+
+	/// The order together with its current bid, total staked votes and voter count.
+	#[rpc(name = "nft_orderDetail")]
+	fn order_detail(
+		&self,
+		order_id: OrderId,
+		at: Option<BlockHash>,
+	) -> Result<Option<OrderDetail<OrderId, NftId, AccountId, Balance, BlockNumber>>>;
+
+	/// Ids of all orders that can be settled now.
+	#[rpc(name = "nft_settleableOrders")]
+	fn settleable_orders(
+		&self,
+		at: Option<BlockHash>,
+	) -> Result<Vec<OrderId>>;
+
+	/// Total funds locked across all orders: bids (including escrowed bids), votes and listing deposits.
+	#[rpc(name = "nft_totalValueLocked")]
+	fn total_value_locked(
+		&self,
+		at: Option<BlockHash>,
+	) -> Result<Balance>;
+
+	/// Ownership history of `nft_id` as (owner, block acquired), oldest first.
+	#[rpc(name = "nft_provenance")]
+	fn provenance(
+		&self,
+		nft_id: NftId,
+		at: Option<BlockHash>,
+	) -> Result<Vec<(AccountId, BlockNumber)>>;
+
+	/// Number of distinct accounts with a vote on `order_id`.
+	#[rpc(name = "nft_voterCount")]
+	fn voter_count(
+		&self,
+		order_id: OrderId,
+		at: Option<BlockHash>,
+	) -> Result<u32>;
+
+	/// Blocks left until `order_id` can be settled; `Some(0)` once settleable, `None` if the order does not exist.
+	#[rpc(name = "nft_blocksUntilSettlement")]
+	fn blocks_until_settlement(
+		&self,
+		order_id: OrderId,
+		at: Option<BlockHash>,
+	) -> Result<Option<BlockNumber>>;
+
+	/// Weight (`amount * vote_day / day`, rounded down) a vote of `amount` locked for `keep_block_num` blocks would get in `order_id`; 0 if the order does not exist.
+	#[rpc(name = "nft_voteWeight")]
+	fn vote_weight(
+		&self,
+		order_id: OrderId,
+		amount: Balance,
+		keep_block_num: BlockNumber,
+		at: Option<BlockHash>,
+	) -> Result<u128>;
+
+	/// Sales statistics of `nft_id` as (sale count, total volume, last sale price).
+	#[rpc(name = "nft_salesStats")]
+	fn sales_stats(
+		&self,
+		nft_id: NftId,
+		at: Option<BlockHash>,
+	) -> Result<(u32, Balance, Balance)>;
+
+	/// Dry-run of `order_sell` by `who`: runs the same checks without changing state and returns the error the extrinsic would fail with.
+	#[rpc(name = "nft_validateOrderSell")]
+	fn validate_order_sell(
+		&self,
+		who: AccountId,
+		nft_id: NftId,
+		start_price: Balance,
+		end_price: Balance,
+		keep_block_num: BlockNumber,
+		params: SellParams<AccountId, BlockNumber>,
+		at: Option<BlockHash>,
+	) -> Result<Result<(), DispatchError>>;
+
+	/// Dry-run of `order_buy`: runs the same checks without changing state and returns the error the extrinsic would fail with.
+	#[rpc(name = "nft_validateOrderBuy")]
+	fn validate_order_buy(
+		&self,
+		order_id: OrderId,
+		price: Balance,
+		max_price: Option<Balance>,
+		at: Option<BlockHash>,
+	) -> Result<Result<(), DispatchError>>;
+
+	/// Status of `order_id` (live, ended with a bid, or ended without one), or `None` if the order does not exist.
+	#[rpc(name = "nft_orderStatus")]
+	fn order_status(
+		&self,
+		order_id: OrderId,
+		at: Option<BlockHash>,
+	) -> Result<Option<OrderStatus>>;
+
+	/// Ids of every existing NFT. Iterates the whole `Nfts` map, so prefer `nft_ids_paged` on large sets.
+	#[rpc(name = "nft_allNftIds")]
+	fn all_nft_ids(
+		&self,
+		at: Option<BlockHash>,
+	) -> Result<Vec<NftId>>;
+
+	/// Up to `limit` NFT ids starting at `start_key` (inclusive), plus the id of the next page.
+	#[rpc(name = "nft_nftIdsPaged")]
+	fn nft_ids_paged(
+		&self,
+		start_key: Option<NftId>,
+		limit: u32,
+		at: Option<BlockHash>,
+	) -> Result<(Vec<NftId>, Option<NftId>)>;
+}
+
+/// A struct that implements the [`NftApi`].
+pub struct Nft<C, B> {
+	client: Arc<C>,
+	_marker: std::marker::PhantomData<B>,
+}
+
+impl<C, B> Nft<C, B> {
+	/// Create new `Nft` with the given reference to the client.
+	pub fn new(client: Arc<C>) -> Self {
+		Nft { client, _marker: Default::default() }
+	}
+}
+
+/// Error code for runtime api failures.
+const RUNTIME_ERROR: i64 = 1;
+
+fn runtime_error<E: std::fmt::Debug>(e: E) -> RpcError {
+	RpcError {
+		code: ErrorCode::ServerError(RUNTIME_ERROR),
+		message: "Unable to query the nft runtime api.".into(),
+		data: Some(format!("{:?}", e).into()),
+	}
+}
+
+impl<C, Block, AccountId, NftId, OrderId, Balance, BlockNumber>
+	NftApi<<Block as BlockT>::Hash, AccountId, NftId, OrderId, Balance, BlockNumber> for Nft<C, Block>
+where
+	Block: BlockT,
+	C: Send + Sync + 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+	C::Api: NftRuntimeApi<Block, AccountId, NftId, OrderId, Balance, BlockNumber>,
+	AccountId: Codec,
+	NftId: Codec,
+	OrderId: Codec,
+	Balance: Codec,
+	BlockNumber: Codec,
+{
+	fn simulate_shares(
+		&self,
+		order_id: OrderId,
+		who: AccountId,
+		amount: Balance,
+		keep_block_num: BlockNumber,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Vec<(AccountId, u128)>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+		api.simulate_shares(&at, order_id, who, amount, keep_block_num).map_err(runtime_error)
+	}
+
+	fn orders_paged(
+		&self,
+		start_key: Option<OrderId>,
+		limit: u32,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<(Vec<(OrderId, Order<OrderId, NftId, AccountId, Balance, BlockNumber>)>, Option<OrderId>)> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+		api.orders_paged(&at, start_key, limit).map_err(runtime_error)
+	}
+
+	fn order_detail(
+		&self,
+		order_id: OrderId,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Option<OrderDetail<OrderId, NftId, AccountId, Balance, BlockNumber>>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+		api.order_detail(&at, order_id).map_err(runtime_error)
+	}
+
+	fn settleable_orders(
+		&self,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Vec<OrderId>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+		api.settleable_orders(&at).map_err(runtime_error)
+	}
+
+	fn total_value_locked(
+		&self,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Balance> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+		api.total_value_locked(&at).map_err(runtime_error)
+	}
+
+	fn provenance(
+		&self,
+		nft_id: NftId,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Vec<(AccountId, BlockNumber)>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+		api.provenance(&at, nft_id).map_err(runtime_error)
+	}
+
+	fn voter_count(
+		&self,
+		order_id: OrderId,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<u32> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+		api.voter_count(&at, order_id).map_err(runtime_error)
+	}
+
+	fn blocks_until_settlement(
+		&self,
+		order_id: OrderId,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Option<BlockNumber>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+		api.blocks_until_settlement(&at, order_id).map_err(runtime_error)
+	}
+
+	fn vote_weight(
+		&self,
+		order_id: OrderId,
+		amount: Balance,
+		keep_block_num: BlockNumber,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<u128> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+		api.vote_weight(&at, order_id, amount, keep_block_num).map_err(runtime_error)
+	}
+
+	fn sales_stats(
+		&self,
+		nft_id: NftId,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<(u32, Balance, Balance)> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+		api.sales_stats(&at, nft_id).map_err(runtime_error)
+	}
+
+	fn validate_order_sell(
+		&self,
+		who: AccountId,
+		nft_id: NftId,
+		start_price: Balance,
+		end_price: Balance,
+		keep_block_num: BlockNumber,
+		params: SellParams<AccountId, BlockNumber>,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Result<(), DispatchError>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+		api.validate_order_sell(&at, who, nft_id, start_price, end_price, keep_block_num, params).map_err(runtime_error)
+	}
+
+	fn validate_order_buy(
+		&self,
+		order_id: OrderId,
+		price: Balance,
+		max_price: Option<Balance>,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Result<(), DispatchError>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+		api.validate_order_buy(&at, order_id, price, max_price).map_err(runtime_error)
+	}
+
+	fn order_status(
+		&self,
+		order_id: OrderId,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Option<OrderStatus>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+		api.order_status(&at, order_id).map_err(runtime_error)
+	}
+
+	fn all_nft_ids(
+		&self,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Vec<NftId>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+		api.all_nft_ids(&at).map_err(runtime_error)
+	}
+
+	fn nft_ids_paged(
+		&self,
+		start_key: Option<NftId>,
+		limit: u32,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<(Vec<NftId>, Option<NftId>)> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+		api.nft_ids_paged(&at, start_key, limit).map_err(runtime_error)
+	}
+}
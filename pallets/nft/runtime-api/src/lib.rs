@@ -0,0 +1,101 @@
+//! Runtime API definition for the nft pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+use sp_std::prelude::*;
+
+pub use pallet_nft::{Order, OrderDetail, AuctionKind, SettlementMode, OrderStatus, SellParams};
+use sp_runtime::DispatchError;
+
+sp_api::decl_runtime_apis! {
+	pub trait NftApi<AccountId, NftId, OrderId, Balance, BlockNumber> where
+		AccountId: Codec,
+		NftId: Codec,
+		OrderId: Codec,
+		Balance: Codec,
+		BlockNumber: Codec,
+	{
+		/// Projected profit of every voter of `order_id` if `who` staked `amount` for
+		/// `keep_block_num` blocks and the order settled at its current price.
+		fn simulate_shares(
+			order_id: OrderId,
+			who: AccountId,
+			amount: Balance,
+			keep_block_num: BlockNumber,
+		) -> Vec<(AccountId, u128)>;
+
+		/// Up to `limit` orders starting at `start_key` (inclusive), plus the key of the next page.
+		fn orders_paged(
+			start_key: Option<OrderId>,
+			limit: u32,
+		) -> (Vec<(OrderId, Order<OrderId, NftId, AccountId, Balance, BlockNumber>)>, Option<OrderId>);
+
+		/// The order together with its current bid, total staked votes and voter count.
+		fn order_detail(order_id: OrderId) -> Option<OrderDetail<OrderId, NftId, AccountId, Balance, BlockNumber>>;
+
+		/// Ids of all orders that can be settled now.
+		fn settleable_orders() -> Vec<OrderId>;
+
+		/// Total funds locked across all orders: bids (including escrowed bids), votes and listing deposits.
+		fn total_value_locked() -> Balance;
+
+		/// Ownership history of `nft_id` as (owner, block acquired), oldest first.
+		fn provenance(
+			nft_id: NftId,
+		) -> Vec<(AccountId, BlockNumber)>;
+
+		/// Number of distinct accounts with a vote on `order_id`.
+		fn voter_count(
+			order_id: OrderId,
+		) -> u32;
+
+		/// Blocks left until `order_id` can be settled; `Some(0)` once settleable, `None` if the order does not exist.
+		fn blocks_until_settlement(
+			order_id: OrderId,
+		) -> Option<BlockNumber>;
+
+		/// Weight (`amount * vote_day / day`, rounded down) a vote of `amount` locked for `keep_block_num` blocks would get in `order_id`; 0 if the order does not exist.
+		fn vote_weight(
+			order_id: OrderId,
+			amount: Balance,
+			keep_block_num: BlockNumber,
+		) -> u128;
+
+		/// Sales statistics of `nft_id` as (sale count, total volume, last sale price).
+		fn sales_stats(
+			nft_id: NftId,
+		) -> (u32, Balance, Balance);
+
+		/// Dry-run of `order_sell` by `who`: runs the same checks without changing state and returns the error the extrinsic would fail with.
+		fn validate_order_sell(
+			who: AccountId,
+			nft_id: NftId,
+			start_price: Balance,
+			end_price: Balance,
+			keep_block_num: BlockNumber,
+			params: SellParams<AccountId, BlockNumber>,
+		) -> Result<(), DispatchError>;
+
+		/// Dry-run of `order_buy`: runs the same checks without changing state and returns the error the extrinsic would fail with.
+		fn validate_order_buy(
+			order_id: OrderId,
+			price: Balance,
+			max_price: Option<Balance>,
+		) -> Result<(), DispatchError>;
+
+		/// Status of `order_id` (live, ended with a bid, or ended without one), or `None` if the order does not exist.
+		fn order_status(
+			order_id: OrderId,
+		) -> Option<OrderStatus>;
+
+		/// Ids of every existing NFT. Iterates the whole `Nfts` map, so prefer `nft_ids_paged` on large sets.
+		fn all_nft_ids() -> Vec<NftId>;
+
+		/// Up to `limit` NFT ids starting at `start_key` (inclusive), plus the id of the next page.
+		fn nft_ids_paged(
+			start_key: Option<NftId>,
+			limit: u32,
+		) -> (Vec<NftId>, Option<NftId>);
+	}
+}
@@ -1,16 +1,18 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
-use codec::{Encode, Decode};
-use frame_support::{debug, ensure, decl_module, decl_storage, decl_event, decl_error, dispatch, traits::{Get, Currency, ReservableCurrency, ExistenceRequirement}, Parameter};
-use frame_system::ensure_signed;
+use codec::{Encode, Decode, DecodeAll};
+#[cfg(feature = "std")]
+use serde::{Serialize, Deserialize};
+use frame_support::{debug, ensure, decl_module, decl_storage, decl_event, decl_error, dispatch, storage::{IterableStorageMap, IterableStorageDoubleMap, StoragePrefixedMap}, traits::{Get, Currency, ReservableCurrency, ExistenceRequirement, BalanceStatus}, weights::{Weight, WithPostDispatchInfo}, transactional, Parameter};
+use frame_system::{ensure_signed, ensure_root};
 use sp_runtime::{
-	DispatchResult, DispatchError, RuntimeDebug,
-	traits::{AtLeast32BitUnsigned, MaybeSerializeDeserialize, Bounded, One, CheckedAdd, CheckedSub},
+	DispatchResult, DispatchError, RuntimeDebug, Permill, PerThing, ModuleId,
+	traits::{AccountIdConversion, AtLeast32BitUnsigned, MaybeSerializeDeserialize, Bounded, One, CheckedAdd, CheckedSub, Saturating, Zero},
 };
-use sp_std::result::Result;
 use sp_std::prelude::*;
 use sp_runtime::SaturatedConversion;
 use substrate_fixed::types::U64F64;
+use sp_arithmetic::helpers_128bit::multiply_by_rational;
 
 #[cfg(test)]
 mod mock;
@@ -33,13 +35,226 @@ pub trait Trait: frame_system::Trait {
 	// 参与质押的分润比例
 	type ProfitRate: Get<f64>;
 	type DayBlockNum: Get<Self::BlockNumber>;
+	// 每个区块最多自动结算的订单数
+	type MaxAutoSettlements: Get<u32>;
+	// 平台手续费比例
+	type PlatformFeeRate: Get<Permill>;
+	// 平台手续费收款账户
+	type FeeAccount: Get<Self::AccountId>;
+	// nft再次成交时注入分红池的比例
+	type DividendRate: Get<Permill>;
+	// 分红池资金托管账户
+	type DividendAccount: Get<Self::AccountId>;
+	// 订单流拍时由平台账户按质押权重补偿质押者的比例, 为0时不补偿
+	type VoterConsolation: Get<Permill>;
+	// 最大拍卖价格, 保证价格转换为U64F64时不会溢出
+	type MaxOrderPrice: Get<BalanceOf<Self>>;
+	// 防狙击窗口, 在到期前该区块数内出价时自动延长拍卖时间
+	type AntiSnipeWindow: Get<Self::BlockNumber>;
+	// 每个账户最多同时存在的拍卖订单数
+	type MaxOrdersPerAccount: Get<u32>;
+	// nft铸造后禁止转移和出售的区块数, 为0时不锁定
+	type MintLockPeriod: Get<Self::BlockNumber>;
+	// 分润算法中初始股权数的上限, 超出时截断
+	type MaxShareStock: Get<u64>;
+	// 订单流拍后最多自动重新挂单的次数
+	type MaxAutoRelists: Get<u32>;
+	// 质押时订单剩余的最少区块数, 临近到期时不允许质押
+	type MinVoteLockBlocks: Get<Self::BlockNumber>;
+	// 每个订单最多保留的出价历史条数
+	type MaxBidHistory: Get<u32>;
+	// 成交时从成交价中奖励给手动结算人的比例
+	type SettlementReward: Get<Permill>;
+	// 流拍时由平台账户奖励给手动结算人的固定金额, 为0时不奖励
+	type CancelSettlementReward: Get<BalanceOf<Self>>;
+	// 每个nft最多保留的所有权历史条数
+	type MaxProvenanceEntries: Get<u32>;
+	// 每个订单除最高出价外最多保留锁定的候补出价数, 为0时被超过的出价立即退还
+	type MaxQueuedBids: Get<u32>;
+	// 挂单时卖家需要锁定的押金
+	type ListingDeposit: Get<BalanceOf<Self>>;
+	// 可以在挂单时设置低于默认平台手续费比例的管理员账户
+	type FeeAdmin: Get<Self::AccountId>;
+	// 打包出售时最多包含的nft数量
+	type MaxBundleSize: Get<u32>;
+	// 价格最小变动单位, 挂单价格和出价必须是其整数倍
+	type PriceTick: Get<BalanceOf<Self>>;
+	// 接收罚没资金的国库账户
+	type TreasuryAccount: Get<Self::AccountId>;
+	// 是否禁止铸造url重复的nft
+	type UniqueUrls: Get<bool>;
+	// nft或订单的剩余可用Id数低于此值时发出IdCounterNearExhaustion事件
+	type IdExhaustionThreshold: Get<u32>;
+	// 一口价购买的冷静期区块数, 为0时立即成交
+	type BuyoutCooldown: Get<Self::BlockNumber>;
+	// 买家在冷静期内取消一口价购买时, 按成交价支付给卖家的违约金比例
+	type BuyoutCancelPenalty: Get<Permill>;
+	// 每个账户在本模块中最多锁定的资金总额(出价、质押和挂单押金), 默认为最大值即不限制
+	type MaxReservedPerAccount: Get<BalanceOf<Self>>;
+	// 每个区块最多新建的订单数
+	type MaxOrdersPerBlock: Get<u32>;
+	// nft链接的最大字节数
+	type MaxUrlLength: Get<u32>;
+	// nft成交后再次挂单前需要等待的区块数, 为0时不限制
+	type RelistCooldown: Get<Self::BlockNumber>;
+	// 卖家是否可以质押自己的订单
+	type AllowSellerVote: Get<bool>;
+	// 是否将竞价资金转入订单的托管账户, 为false时锁定在竞价者账户中
+	type UseEscrow: Get<bool>;
+	// 用于派生订单托管账户的模块Id
+	type EscrowModuleId: Get<ModuleId>;
+	// 卖家撤销已有竞价的订单时, 按最高出价支付给最高出价者的补偿比例
+	type CancelPenalty: Get<Permill>;
+	// 批量结算每次最多包含的订单数量
+	type MaxSettlementBatch: Get<u32>;
+	// 成交时分配利润所需的最少质押人数
+	type MinVotersForPayout: Get<u32>;
+	// 每个nft最多可以挂单的次数
+	type MaxListingsPerNft: Get<u32>;
+	// 年化收益计算中每年的天数, 一天的区块数为DayBlockNum
+	type DaysPerYear: Get<u32>;
+	// 每个订单的质押总额上限
+	type MaxTotalVotesPerOrder: Get<BalanceOf<Self>>;
+	// 提前成交时质押是否继续锁定到订单原定的到期区块
+	type HonorFullVoteLock: Get<bool>;
+	// 卖家撤销没有竞价的订单时支付给国库账户的手续费
+	type CancellationFee: Get<BalanceOf<Self>>;
+	// 每个合集最多包含的nft数量
+	type MaxCollectionSize: Get<u32>;
+	// 关闭合集时每次调用最多处理的nft数量
+	type CollectionCloseBatch: Get<u32>;
 	type NftId: Parameter + AtLeast32BitUnsigned + Default + Copy + MaybeSerializeDeserialize + Bounded;
 	type OrderId: Parameter + AtLeast32BitUnsigned + Default + Copy + MaybeSerializeDeserialize + Bounded;
+	// 链上原生货币, 余额类型以此为准
 	type Currency: ReservableCurrency<Self::AccountId>;
+	// 用于出价、质押、手续费和分红的支付货币, 可以是稳定币等其他资产, 也可以直接设为Currency
+	type PaymentCurrency: ReservableCurrency<Self::AccountId, Balance = BalanceOf<Self>>;
 }
 
+// 拍卖类型
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Encode, Decode, Clone, Copy, RuntimeDebug, Eq, PartialEq)]
+pub enum AuctionKind {
+	// 英式拍卖, 价格递增竞价, 达到结拍价格时直接成交
+	English,
+	// 荷兰式拍卖, 价格从结拍价格随时间递减到起拍价格, 第一个买家成交
+	Dutch,
+	// 一口价, 只能以起拍价格直接成交, 不允许竞价
+	FixedPrice,
+}
+
+// Id计数器类型
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Encode, Decode, Clone, Copy, RuntimeDebug, Eq, PartialEq)]
+pub enum IdCounter {
+	// NextNftId
+	Nft,
+	// NextOrderId
+	Order,
+}
+
+// 结算方式
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Encode, Decode, Clone, Copy, RuntimeDebug, Eq, PartialEq)]
+pub enum SettlementMode {
+	// 第一价格, 成交者支付自己的出价
+	FirstPrice,
+	// 第二价格(维克里拍卖), 成交者支付次高出价(没有次高出价时为起拍价格), 差额退还
+	SecondPrice,
+}
+
+// 订单状态, 用于前端按状态筛选订单
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Encode, Decode, Clone, Copy, RuntimeDebug, Eq, PartialEq)]
+pub enum OrderStatus {
+	// 拍卖进行中
+	Live,
+	// 拍卖已结束, 有竞价, 等待结算成交
+	EndedWithBid,
+	// 拍卖已结束, 没有竞价, 等待结算流拍
+	EndedNoBid,
+}
+
+// 存储版本, on_runtime_upgrade按版本依次执行迁移
+#[allow(non_camel_case_types)]
+#[derive(Encode, Decode, Clone, Copy, RuntimeDebug, Eq, PartialEq, PartialOrd, Ord)]
+pub enum Releases {
+	// 初始版本, nft详情为Nft, 订单只有基本字段
+	V1_0_0,
+	// nft详情迁移为NftMetadata
+	V2_0_0,
+	// 订单迁移为包含expire_block等字段的格式
+	V3_0_0,
+}
+
+impl Default for Releases {
+	fn default() -> Self {
+		Releases::V1_0_0
+	}
+}
+
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 #[derive(Encode, Decode, Clone, RuntimeDebug, Eq, PartialEq)]
 pub struct Order<OrderId, NftId, AccountId, Balance, BlockNumber> {
+	pub order_id: OrderId,
+	pub start_price: Balance,
+	pub end_price: Balance,
+	pub nft_id: NftId,
+	pub create_block: BlockNumber,
+	// 开始接受竞价和质押的区块, 在此之前订单可见但不能竞价
+	pub start_block: BlockNumber,
+	pub keep_block_num: BlockNumber,
+	pub owner: AccountId,
+	pub expire_block: BlockNumber,
+	pub kind: AuctionKind,
+	// 流拍时是否自动以相同参数重新挂单
+	pub auto_relist: bool,
+	// 已经自动重新挂单的次数
+	pub relist_count: u32,
+	// 卖家锁定的挂单押金
+	pub deposit: Balance,
+	// 订单的平台手续费比例, None时使用默认的PlatformFeeRate
+	pub fee_rate: Option<Permill>,
+	// 订单的结算方式
+	pub settlement_mode: SettlementMode,
+	// 卖家所得的收款账户及比例, 比例之和为100%, 为空时全部归卖家
+	pub payees: Vec<(AccountId, Permill)>,
+}
+
+// 挂单的可选设置, 未设置的字段取默认值: 英式拍卖、不自动重新挂单、立即开始、默认手续费、第一价格结算、全部归卖家
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Encode, Decode, Clone, RuntimeDebug, Eq, PartialEq)]
+pub struct SellParams<AccountId, BlockNumber> {
+	// 拍卖类型
+	pub kind: AuctionKind,
+	// 流拍时是否自动以相同参数重新挂单
+	pub auto_relist: bool,
+	// 延迟开始的区块数, 订单在此期间可见但不能竞价和质押
+	pub start_delay: Option<BlockNumber>,
+	// 订单的平台手续费比例, None时使用默认的PlatformFeeRate
+	pub fee_rate: Option<Permill>,
+	// 订单的结算方式
+	pub settlement_mode: SettlementMode,
+	// 卖家所得的收款账户及比例, 比例之和为100%, 为空时全部归卖家
+	pub payees: Vec<(AccountId, Permill)>,
+}
+
+impl<AccountId, BlockNumber> Default for SellParams<AccountId, BlockNumber> {
+	fn default() -> Self {
+		SellParams {
+			kind: AuctionKind::English,
+			auto_relist: false,
+			start_delay: None,
+			fee_rate: None,
+			settlement_mode: SettlementMode::FirstPrice,
+			payees: Vec::new(),
+		}
+	}
+}
+
+// 初始版本的订单, 仅用于迁移已有数据
+#[derive(Encode, Decode, Clone, RuntimeDebug)]
+pub struct LegacyOrder<OrderId, NftId, AccountId, Balance, BlockNumber> {
 	pub order_id: OrderId,
 	pub start_price: Balance,
 	pub end_price: Balance,
@@ -49,6 +264,7 @@ pub struct Order<OrderId, NftId, AccountId, Balance, BlockNumber> {
 	pub owner: AccountId,
 }
 
+// 旧版本的nft详情, 仅用于迁移已有数据
 #[derive(Encode, Decode, Clone, RuntimeDebug)]
 pub struct Nft {
 	pub title: Vec<u8>,
@@ -56,6 +272,20 @@ pub struct Nft {
 	pub desc: Vec<u8>,
 }
 
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Encode, Decode, Clone, RuntimeDebug, Eq, PartialEq)]
+pub struct NftMetadata<BlockNumber> {
+	pub title: Vec<u8>,
+	// 链接, 长度不超过MaxUrlLength
+	pub uri: Vec<u8>,
+	pub desc: Vec<u8>,
+	// 内容类型, 由前端约定
+	pub content_type: u8,
+	// 铸造时的区块号
+	pub created_at: BlockNumber,
+}
+
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 #[derive(Encode, Decode, Clone, RuntimeDebug, Eq, PartialEq)]
 pub struct Bid<OrderId, AccountId, Balance> {
 	pub order_id: OrderId,
@@ -71,17 +301,75 @@ pub struct Vote<OrderId, AccountId, Balance, BlockNumber> {
 	pub owner: AccountId,
 }
 
+// nft合集, 关闭后合集中的nft不能再挂单
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Encode, Decode, Clone, RuntimeDebug, Eq, PartialEq)]
+pub struct Collection<AccountId> {
+	pub owner: AccountId,
+	// 是否已关闭, 分批关闭期间也为true
+	pub closed: bool,
+}
+
+// 订单详情汇总, 用于前端一次查询订单、当前出价和质押概况
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Encode, Decode, Clone, RuntimeDebug, Eq, PartialEq)]
+pub struct OrderDetail<OrderId, NftId, AccountId, Balance, BlockNumber> {
+	pub order: Order<OrderId, NftId, AccountId, Balance, BlockNumber>,
+	pub bid: Option<Bid<OrderId, AccountId, Balance>>,
+	pub total_votes: Balance,
+	pub voter_count: u32,
+}
+
+pub type CollectionId = u32;
 type BalanceOf<T> = <<T as Trait>::Currency as Currency<<T as frame_system::Trait>::AccountId>>::Balance;
-type OrderOf<T> = Order<<T as Trait>::OrderId, <T as Trait>::NftId, <T as frame_system::Trait>::AccountId, BalanceOf<T>, <T as frame_system::Trait>::BlockNumber>;
+pub type NftMetadataOf<T> = NftMetadata<<T as frame_system::Trait>::BlockNumber>;
+pub type LegacyOrderOf<T> = LegacyOrder<<T as Trait>::OrderId, <T as Trait>::NftId, <T as frame_system::Trait>::AccountId, BalanceOf<T>, <T as frame_system::Trait>::BlockNumber>;
+pub type OrderOf<T> = Order<<T as Trait>::OrderId, <T as Trait>::NftId, <T as frame_system::Trait>::AccountId, BalanceOf<T>, <T as frame_system::Trait>::BlockNumber>;
+pub type SellParamsOf<T> = SellParams<<T as frame_system::Trait>::AccountId, <T as frame_system::Trait>::BlockNumber>;
+pub type OrderDetailOf<T> = OrderDetail<<T as Trait>::OrderId, <T as Trait>::NftId, <T as frame_system::Trait>::AccountId, BalanceOf<T>, <T as frame_system::Trait>::BlockNumber>;
 type BidOf<T> = Bid<<T as Trait>::OrderId, <T as frame_system::Trait>::AccountId, BalanceOf<T>>;
 type VoteOf<T> = Vote<<T as Trait>::OrderId, <T as frame_system::Trait>::AccountId, BalanceOf<T>, <T as frame_system::Trait>::BlockNumber>;
 
 decl_storage! {
 	trait Store for Module<T: Trait> as NftModule {
 		// nftId -> nft详情， 用于存储所有nft
-		pub Nfts: map hasher(twox_64_concat) T::NftId => Option<Nft>;
+		pub Nfts: map hasher(twox_64_concat) T::NftId => Option<NftMetadataOf<T>>;
 		// nftId -> 账户Id， 用于记录nft所有者
 		pub NftAccount: map hasher(twox_64_concat) T::NftId => T::AccountId;
+		// 账户Id -> nftId列表, 用于查询账户持有的nft
+		pub OwnedNfts get(fn owned_nfts): map hasher(twox_64_concat) T::AccountId => Vec<T::NftId>;
+		// nftId -> 所有权历史(所有者, 取得所有权的区块号), 最多保留MaxProvenanceEntries条
+		pub OwnershipHistory: map hasher(twox_64_concat) T::NftId => Vec<(T::AccountId, T::BlockNumber)>;
+		// nftId -> (成交次数, 累计成交额, 最近成交价), 所有权变更时保留
+		pub NftSalesStats: map hasher(twox_64_concat) T::NftId => (u32, BalanceOf<T>, BalanceOf<T>);
+		// nftId -> 最近成交的区块号, 用于再次挂单冷却期检查
+		pub LastSoldBlock: map hasher(twox_64_concat) T::NftId => Option<T::BlockNumber>;
+		// nftId -> 已经挂单的次数, 不超过MaxListingsPerNft
+		pub NftListCount: map hasher(twox_64_concat) T::NftId => u32;
+		// 订单Id -> 订单的质押总额, 不超过MaxTotalVotesPerOrder
+		pub OrderVoteTotal: map hasher(twox_64_concat) T::OrderId => BalanceOf<T>;
+		// (订单Id, 质押者) -> 提前成交后继续锁定的质押数量及可以解锁的区块
+		pub LockedVotes: double_map hasher(twox_64_concat) T::OrderId, hasher(twox_64_concat) T::AccountId => Option<(BalanceOf<T>, T::BlockNumber)>;
+		// nftId -> 铸造时的区块号, 用于铸造锁定期检查
+		pub NftMintBlock: map hasher(twox_64_concat) T::NftId => T::BlockNumber;
+		// nftId -> 是否已被销毁, 用于区分从未存在和已销毁的nft
+		pub BurnedNfts: map hasher(twox_64_concat) T::NftId => bool;
+		// nftId -> 元数据哈希, 用于校验链下资源是否被修改, 只能通过update_hash修改
+		pub NftHash: map hasher(twox_64_concat) T::NftId => [u8; 32];
+		// url -> 是否已被使用, 仅在开启UniqueUrls时记录, nft移除时清除
+		pub UrlSeen: map hasher(blake2_128_concat) Vec<u8> => bool;
+		// nftId -> 是否冻结, 冻结的nft不能转移、出售和移除
+		pub FrozenNfts: map hasher(twox_64_concat) T::NftId => bool;
+		// nftId -> 是否由root冻结, root冻结的nft只有root可以解冻
+		pub RootFrozenNfts: map hasher(twox_64_concat) T::NftId => bool;
+		// (订单Id, 竞价者) -> 从托管账户退还失败、等待竞价者领取的出价, 资金仍计入锁定总额
+		pub PendingBidRefunds: double_map hasher(twox_64_concat) T::OrderId, hasher(twox_64_concat) T::AccountId => BalanceOf<T>;
+		// 账户Id -> 是否拒绝接收转移的nft, 不影响通过拍卖购买nft
+		pub TransfersBlocked: map hasher(twox_64_concat) T::AccountId => bool;
+		// 委托人账户Id -> 竞拍代理账户Id, 代理账户可以用委托人的资金竞拍
+		pub BidDelegates: map hasher(twox_64_concat) T::AccountId => Option<T::AccountId>;
+		// 所有者账户Id, 操作员账户Id -> 是否授权操作员转移和出售所有者的全部nft
+		pub OperatorApprovals: double_map hasher(twox_64_concat) T::AccountId, hasher(twox_64_concat) T::AccountId => bool;
 
 		// nftId -> 订单Id， 用于记录Nft对应的订单数据
 		pub NftOrder: map hasher(twox_64_concat) T::NftId => Option<T::OrderId>;
@@ -89,13 +377,63 @@ decl_storage! {
 		pub Orders: map hasher(twox_64_concat) T::OrderId => Option<OrderOf<T>>;
 		// 订单Id -> 当前最大出价，用于存储当前订单的最大出价
 		pub Bids: map hasher(twox_64_concat) T::OrderId => Option<BidOf<T>>;
+		// 订单Id -> 出价历史(出价人, 价格, 区块号), 最多保留MaxBidHistory条
+		pub BidHistory: map hasher(twox_64_concat) T::OrderId => Vec<(T::AccountId, BalanceOf<T>, T::BlockNumber)>;
+		// 订单Id -> 候补出价列表, 按价格从高到低排列, 资金保持锁定, 最多保留MaxQueuedBids个
+		pub BidQueue: map hasher(twox_64_concat) T::OrderId => Vec<BidOf<T>>;
+		// 订单Id -> 打包出售的所有nft, 第一个为订单的nft_id, 单个nft出售的订单没有记录
+		pub BundleOrders: map hasher(twox_64_concat) T::OrderId => Vec<T::NftId>;
 		// 订单Id -> 质押投票列表, 用于存储质押列表
 		pub Votes: map hasher(twox_64_concat) T::OrderId => Vec<VoteOf<T>>;
+		// 订单Id -> 不同质押账户的数量, 与Votes同步维护
+		pub VoterCount: map hasher(twox_64_concat) T::OrderId => u32;
+		// 账户Id -> 正在拍卖中的订单数
+		pub OrderCountByOwner: map hasher(twox_64_concat) T::AccountId => u32;
+		// 订单Id -> (), 正在拍卖中的订单索引, 用于浏览所有在售订单
+		pub ActiveOrders: map hasher(twox_64_concat) T::OrderId => ();
+		// 订单Id -> (), 自动结算失败的订单, 竞价已退还, 等待手动结算
+		pub FailedOrders: map hasher(twox_64_concat) T::OrderId => ();
+		// 区块号 -> 订单Id列表, 用于在on_initialize中自动结算到期订单
+		pub ExpiringOrders: map hasher(twox_64_concat) T::BlockNumber => Vec<T::OrderId>;
+		// 订单Id -> (买家, 成交价, 自动成交区块), 冷静期内的一口价购买, 买家资金保持锁定
+		pub PendingBuyouts: map hasher(twox_64_concat) T::OrderId => Option<(T::AccountId, BalanceOf<T>, T::BlockNumber)>;
+		// 区块号 -> 订单Id列表, 用于在on_initialize中自动确认冷静期结束的一口价购买
+		pub ExpiringBuyouts: map hasher(twox_64_concat) T::BlockNumber => Vec<T::OrderId>;
+
+		// 所有订单中锁定的资金总额(出价、质押和挂单押金, 包括转入托管账户的出价)
+		pub TotalReserved: BalanceOf<T>;
+		// 账户Id -> 该账户在本模块中锁定的资金总额(出价、质押和挂单押金, 包括转入托管账户的出价)
+		pub ReservedByAccount: map hasher(blake2_128_concat) T::AccountId => BalanceOf<T>;
+		// 紧急暂停开关, 暂停时不能挂单、竞拍、质押、撤单、改价和结算, 也不能领取分红
+		pub Paused: bool;
+		// 存储版本, 在on_runtime_upgrade中检查并提升
+		pub StorageVersion get(fn storage_version): Releases;
+		// 当前区块已经新建的订单数, 在on_initialize中重置
+		pub OrdersThisBlock: u32;
+
+		// nftId, 账户Id -> 持有的分红股份, 质押者在订单成交时按分润获得股份
+		pub NftShares get(fn nft_shares): double_map hasher(twox_64_concat) T::NftId, hasher(twox_64_concat) T::AccountId => BalanceOf<T>;
+		// nftId -> 分红股份总数
+		pub NftTotalShares: map hasher(twox_64_concat) T::NftId => BalanceOf<T>;
+		// nftId -> 分红池中尚未领取的资金总数
+		pub DividendPool: map hasher(twox_64_concat) T::NftId => BalanceOf<T>;
+		// nftId, 账户Id -> 可领取的分红
+		pub Dividends get(fn dividends): double_map hasher(twox_64_concat) T::NftId, hasher(twox_64_concat) T::AccountId => BalanceOf<T>;
 
 		// NftId生成器，递增
 		pub NextNftId: T::NftId;
 		// 拍卖订单Id生成器，递增
 		pub NextOrderId: T::OrderId;
+		// 合集Id生成器，递增
+		pub NextCollectionId: CollectionId;
+		// 合集Id -> 合集详情
+		pub Collections: map hasher(twox_64_concat) CollectionId => Option<Collection<T::AccountId>>;
+		// 合集Id -> 合集中的nft列表, 不超过MaxCollectionSize
+		pub CollectionNfts: map hasher(twox_64_concat) CollectionId => Vec<T::NftId>;
+		// nftId -> 所属合集Id, 每个nft最多属于一个合集
+		pub NftCollection: map hasher(twox_64_concat) T::NftId => Option<CollectionId>;
+		// 合集Id -> 分批关闭时下一个待处理的nft在CollectionNfts中的位置, 关闭完成后移除
+		pub CollectionCloseCursor: map hasher(twox_64_concat) CollectionId => Option<u32>;
 	}
 }
 
@@ -104,16 +442,72 @@ decl_event!(
 		<T as Trait>::NftId,
 		<T as Trait>::OrderId,
 		AccountId = <T as frame_system::Trait>::AccountId,
+		Balance = BalanceOf<T>,
+		BlockNumber = <T as frame_system::Trait>::BlockNumber,
 	{
-		NftCreated(AccountId, NftId),
+		// 创建nft, 最后一个参数为元数据哈希
+		NftCreated(AccountId, NftId, [u8; 32]),
 		NftRemove(AccountId, NftId),
 		NftTransfer(AccountId, AccountId, NftId),
+		NftFrozenChanged(NftId, bool),
+		// 更新元数据哈希, 最后一个参数为新的哈希
+		NftHashUpdated(AccountId, NftId, [u8; 32]),
+		// 账户设置是否拒绝接收nft转移
+		TransfersBlockedChanged(AccountId, bool),
+		// 设置竞拍代理账户, 第二个参数为代理账户, None表示取消
+		BidDelegateChanged(AccountId, Option<AccountId>),
+		// 所有者授权或取消操作员管理其全部nft, 参数为所有者、操作员和是否授权
+		ApprovalForAll(AccountId, AccountId, bool),
 
-		OrderSell(AccountId, OrderId),
+		// 创建订单, 最后一个参数为到期区块
+		OrderSell(AccountId, OrderId, BlockNumber),
 		OrderBuy(AccountId, OrderId),
+		OrderBuyNow(AccountId, OrderId, Balance),
 
 		OrderComplete(AccountId, OrderId),
 		OrderCancel(AccountId, OrderId),
+		// 修改订单价格, 参数为新的起拍价格和结拍价格
+		OrderPriceUpdated(AccountId, OrderId, Balance, Balance),
+		// 订单延期, 最后一个参数为新的到期区块
+		OrderExtended(AccountId, OrderId, BlockNumber),
+		// 结算时退还质押, 参数为质押者、订单Id和退还的质押数量
+		VoteRefunded(AccountId, OrderId, Balance),
+
+		// 自动结算失败, 竞价已退还, 订单等待手动结算
+		OrderFailed(OrderId),
+
+		// 设置紧急暂停开关
+		PausedChanged(bool),
+
+		DividendClaimed(AccountId, NftId, Balance),
+		// Id计数器剩余可用的Id数低于IdExhaustionThreshold, 计数器类型, 剩余Id数
+		IdCounterNearExhaustion(IdCounter, u128),
+		// 一口价购买进入冷静期, 买家, 订单Id, 自动成交区块
+		BuyoutPending(AccountId, OrderId, BlockNumber),
+		// 买家在冷静期内取消一口价购买, 买家, 订单Id, 支付给卖家的违约金
+		BuyoutCancelled(AccountId, OrderId, Balance),
+		// 质押成功, 质押者, 订单Id, 本次质押数量, 本次质押的区块长度
+		VotePlaced(AccountId, OrderId, Balance, BlockNumber),
+		// 卖家撤销订单时向最高出价者支付补偿: 卖家, 订单Id, 最高出价者, 补偿金额
+		CancelPenaltyPaid(AccountId, OrderId, AccountId, Balance),
+		// 批量结算完成: 结算人, 已结算的订单数量, 跳过的订单数量
+		SettlementBatch(AccountId, u32, u32),
+		// 订单的卖家权利被转让: 订单Id, 原卖家, 新卖家
+		OrderOwnershipTransferred(OrderId, AccountId, AccountId),
+		// 提前成交后质押继续锁定: 质押者, 订单Id, 质押数量, 可以解锁的区块
+		VoteLocked(AccountId, OrderId, Balance, BlockNumber),
+		// 从托管账户退还出价失败, 等待竞价者调用claim_bid_refund领取: 竞价者, 订单Id, 出价数量
+		BidRefundPending(AccountId, OrderId, Balance),
+		// 竞价者领取了退还失败的出价: 竞价者, 订单Id, 出价数量
+		BidRefundClaimed(AccountId, OrderId, Balance),
+		// 创建合集: 所有者, 合集Id
+		CollectionCreated(AccountId, CollectionId),
+		// nft加入合集: 合集Id, nftId
+		CollectionNftAdded(CollectionId, NftId),
+		// 合集分批关闭中, 合集Id, 已处理的nft数量
+		CollectionClosing(CollectionId, u32),
+		// 合集关闭完成, 合集中的所有订单已撤销
+		CollectionClosed(CollectionId),
 	}
 );
 
@@ -136,6 +530,107 @@ decl_error! {
 		PriceTooLow,
 		StartPriceTooLow,
 		VoteAmountTooLow,
+		VoteAmountOverflow,
+		InsufficientReservedBalance,
+		NftFrozen,
+		NoDividend,
+		InsufficientBalanceForVote,
+		OrderPriceTooBig,
+		TooManyOrders,
+		ReduceTooLarge,
+		NftLocked,
+		BiddingNotAllowed,
+		ShareOverflow,
+		PriceExceedsMax,
+		VoteWindowClosed,
+		NftBurned,
+		OrderHasBid,
+		RecipientNotLive,
+		// 订单尚未到开始区块, 不能竞价和质押
+		OrderNotStarted,
+		// 接收人拒绝接收转移的nft
+		RecipientBlocksTransfers,
+		// 调用者不是委托人设置的竞拍代理账户
+		NotBidDelegate,
+		// 交易已被紧急暂停
+		Paused,
+		// 只有管理员可以设置低于默认比例的手续费
+		FeeRateNotAllowed,
+		// 打包出售至少需要两个nft
+		BundleTooSmall,
+		// 打包出售的nft数量超过MaxBundleSize
+		BundleTooBig,
+		// 打包出售的nft有重复
+		BundleDuplicateNft,
+		// 价格不是最小变动单位的整数倍
+		PriceNotOnTick,
+		// 出价低于订单的起拍价格
+		BelowStartPrice,
+		// 出价不高于当前最高出价
+		BelowCurrentBid,
+		// nft所有者账户仍然有效, 不能收回
+		OwnerStillActive,
+		// 第二价格结算只适用于英式拍卖
+		SecondPriceNotEnglish,
+		// url已被其他nft使用
+		DuplicateUrl,
+		// 订单有冷静期内的一口价购买
+		BuyoutPending,
+		// 订单没有冷静期内的一口价购买
+		NoPendingBuyout,
+		// 调用者不是一口价购买的买家
+		NotBuyoutBuyer,
+		// 账户锁定的资金总额将超过MaxReservedPerAccount
+		ReservedLimitExceeded,
+		// 收款账户的比例之和不等于100%
+		PayeeSplitInvalid,
+		// 当前区块新建的订单数已达到MaxOrdersPerBlock
+		BlockOrderLimitReached,
+		// nft链接超过MaxUrlLength
+		UrlTooLong,
+		// nft在成交后的再次挂单冷却期内
+		RelistCooldownActive,
+		// 卖家不能质押自己的订单
+		SellerCannotVote,
+		// 卖家无法支付撤销订单的补偿
+		CancelPenaltyUnaffordable,
+		// 批量结算的订单数量超过MaxSettlementBatch
+		SettlementBatchTooLarge,
+		// nft的挂单次数已达到MaxListingsPerNft
+		ListingLimitReached,
+		// 竞价者的可用余额不足以锁定竞价资金
+		InsufficientBalanceForBid,
+		// 卖家的可用余额不足以锁定挂单押金
+		InsufficientBalanceForDeposit,
+		// 订单的质押总额超过MaxTotalVotesPerOrder
+		OrderVoteCapReached,
+		// 没有继续锁定的质押
+		NoLockedVote,
+		// 质押尚未到期, 不能解锁
+		VoteStillLocked,
+		// 卖家无法支付撤销订单的手续费
+		CancellationFeeUnaffordable,
+		// nft由root冻结, 只有root可以解冻
+		FrozenByRoot,
+		// 分红和质押者利润超过扣除手续费后的卖家所得
+		PayoutExceedsProceeds,
+		// 订单的手续费比例与结算奖励、分红和分润比例之和超过100%
+		FeeRateTooHigh,
+		// 托管账户尚不存在时, 出价低于支付货币的最低存在余额, 无法转入托管账户
+		BidBelowExistentialDeposit,
+		// 没有等待领取的出价退款
+		NoBidRefund,
+		CollectionIdOverflow,
+		CollectionNotExist,
+		NotCollectionOwner,
+		// 合集已关闭, 不能加入nft或挂单
+		CollectionClosed,
+		// 合集已经关闭完成
+		CollectionAlreadyClosed,
+		// 合集中的nft数量达到MaxCollectionSize
+		CollectionFull,
+		// nft已经属于某个合集
+		NftInCollection,
 	}
 }
 
@@ -149,326 +644,2109 @@ decl_module! {
 		const MaxKeepBlockNumber: T::BlockNumber = T::MaxKeepBlockNumber::get();
 		const MinimumPrice: BalanceOf<T> = T::MinimumPrice::get();
 		const MinimumVotingLock: BalanceOf<T> = T::MinimumVotingLock::get();
+		const MaxAutoSettlements: u32 = T::MaxAutoSettlements::get();
+		const PlatformFeeRate: Permill = T::PlatformFeeRate::get();
+		const FeeAccount: T::AccountId = T::FeeAccount::get();
+		const DividendRate: Permill = T::DividendRate::get();
+		const DividendAccount: T::AccountId = T::DividendAccount::get();
+		const VoterConsolation: Permill = T::VoterConsolation::get();
+		const MaxOrderPrice: BalanceOf<T> = T::MaxOrderPrice::get();
+		const AntiSnipeWindow: T::BlockNumber = T::AntiSnipeWindow::get();
+		const MaxOrdersPerAccount: u32 = T::MaxOrdersPerAccount::get();
+		const MintLockPeriod: T::BlockNumber = T::MintLockPeriod::get();
+		const MaxShareStock: u64 = T::MaxShareStock::get();
+		const MaxAutoRelists: u32 = T::MaxAutoRelists::get();
+		const MinVoteLockBlocks: T::BlockNumber = T::MinVoteLockBlocks::get();
+		const MaxBidHistory: u32 = T::MaxBidHistory::get();
+		const SettlementReward: Permill = T::SettlementReward::get();
+		const CancelSettlementReward: BalanceOf<T> = T::CancelSettlementReward::get();
+		const MaxProvenanceEntries: u32 = T::MaxProvenanceEntries::get();
+		const MaxQueuedBids: u32 = T::MaxQueuedBids::get();
+		const ListingDeposit: BalanceOf<T> = T::ListingDeposit::get();
+		const FeeAdmin: T::AccountId = T::FeeAdmin::get();
+		const MaxBundleSize: u32 = T::MaxBundleSize::get();
+		const PriceTick: BalanceOf<T> = T::PriceTick::get();
+		const TreasuryAccount: T::AccountId = T::TreasuryAccount::get();
+		const UniqueUrls: bool = T::UniqueUrls::get();
+		const IdExhaustionThreshold: u32 = T::IdExhaustionThreshold::get();
+		const BuyoutCooldown: T::BlockNumber = T::BuyoutCooldown::get();
+		const BuyoutCancelPenalty: Permill = T::BuyoutCancelPenalty::get();
+		const MaxReservedPerAccount: BalanceOf<T> = T::MaxReservedPerAccount::get();
+		const MaxOrdersPerBlock: u32 = T::MaxOrdersPerBlock::get();
+		const MaxUrlLength: u32 = T::MaxUrlLength::get();
+		const RelistCooldown: T::BlockNumber = T::RelistCooldown::get();
+		const AllowSellerVote: bool = T::AllowSellerVote::get();
+		const UseEscrow: bool = T::UseEscrow::get();
+		const EscrowModuleId: ModuleId = T::EscrowModuleId::get();
+		const CancelPenalty: Permill = T::CancelPenalty::get();
+		const MaxSettlementBatch: u32 = T::MaxSettlementBatch::get();
+		const MinVotersForPayout: u32 = T::MinVotersForPayout::get();
+		const MaxListingsPerNft: u32 = T::MaxListingsPerNft::get();
+		const DaysPerYear: u32 = T::DaysPerYear::get();
+		const MaxTotalVotesPerOrder: BalanceOf<T> = T::MaxTotalVotesPerOrder::get();
+		const HonorFullVoteLock: bool = T::HonorFullVoteLock::get();
+		const CancellationFee: BalanceOf<T> = T::CancellationFee::get();
+		const MaxCollectionSize: u32 = T::MaxCollectionSize::get();
+		const CollectionCloseBatch: u32 = T::CollectionCloseBatch::get();
+
+		// 检查配置的比例: 默认手续费、结算奖励、分红和质押者分润之和不能超过成交价
+		fn integrity_test() {
+			assert!(
+				Self::deductions_within_price(T::PlatformFeeRate::get()),
+				"PlatformFeeRate + SettlementReward + DividendRate + ProfitRate must not exceed 100%",
+			);
+		}
+
+		// 按存储版本依次执行迁移, 每个迁移完成后提升版本, 已经是最新版本时不做任何修改
+		fn on_runtime_upgrade() -> Weight {
+			let mut weight = T::DbWeight::get().reads(1);
+			// 将旧版本的nft详情迁移为NftMetadata
+			if StorageVersion::get() < Releases::V2_0_0 {
+				weight = weight.saturating_add(Self::migrate_nft_metadata());
+				StorageVersion::put(Releases::V2_0_0);
+				weight = weight.saturating_add(T::DbWeight::get().writes(1));
+			}
+			// 将初始版本的订单迁移为包含expire_block的格式
+			if StorageVersion::get() < Releases::V3_0_0 {
+				weight = weight.saturating_add(Self::migrate_legacy_orders());
+				StorageVersion::put(Releases::V3_0_0);
+				weight = weight.saturating_add(T::DbWeight::get().writes(1));
+			}
+			weight
+		}
+
+		// 自动结算到期的订单, 每个区块最多结算MaxAutoSettlements个, 剩余的顺延到下一个区块
+		fn on_initialize(now: T::BlockNumber) -> Weight {
+			// 重置当前区块的新建订单计数
+			OrdersThisBlock::kill();
+			// 暂停期间不自动结算, 到期的订单和一口价购买顺延到下一个区块
+			if Paused::get() {
+				let next = now + One::one();
+				let buyout_ids = ExpiringBuyouts::<T>::take(now);
+				let order_ids = ExpiringOrders::<T>::take(now);
+				if !buyout_ids.is_empty() {
+					ExpiringBuyouts::<T>::mutate(next, |ids| ids.extend(buyout_ids));
+				}
+				if !order_ids.is_empty() {
+					ExpiringOrders::<T>::mutate(next, |ids| ids.extend(order_ids));
+				}
+				return T::DbWeight::get().reads_writes(3, 5);
+			}
+			// 冷静期结束的一口价购买自动成交
+			let buyout_weight = Self::finalize_expired_buyouts(now).saturating_add(T::DbWeight::get().writes(1));
+			let mut order_ids: Vec<T::OrderId> = ExpiringOrders::<T>::take(now);
+			if order_ids.is_empty() {
+				return T::DbWeight::get().reads(1).saturating_add(buyout_weight);
+			}
+			let max = T::MaxAutoSettlements::get() as usize;
+			if order_ids.len() > max {
+				let rest = order_ids.split_off(max);
+				ExpiringOrders::<T>::mutate(now + One::one(), |ids| ids.extend(rest));
+			}
+			let mut settled: Weight = 0;
+			for order_id in order_ids.iter() {
+				// 订单已被手动结算或已成交
+				let order: OrderOf<T> = match Orders::<T>::get(order_id) {
+					Some(order) => order,
+					None => continue,
+				};
+				// 冷静期内的一口价购买由finalize_expired_buyouts处理
+				if !Self::is_time_to_settlement(&order) || PendingBuyouts::<T>::contains_key(order_id) {
+					continue;
+				}
+				if let Err(e) = Self::settle_order(&order, None) {
+					debug::warn!("=>自动结算订单失败: {:?}, {:?}", order_id, e);
+					// 有竞价时先尝试直接转移锁定的竞价资金成交, 仍然失败时再退还竞价并标记订单
+					let fallback = Bids::<T>::get(order_id).map(|bid| Self::fallback_settle(&order, &bid));
+					if !matches!(fallback, Some(Ok(()))) {
+						Self::fail_order(&order, Self::is_seller_default(&e));
+					}
+				}
+				settled += 1;
+			}
+			T::DbWeight::get().reads_writes(1 + order_ids.len() as Weight, 1)
+				.saturating_add(settled.saturating_mul(10_000 + T::DbWeight::get().reads_writes(20, 25)))
+				.saturating_add(buyout_weight)
+		}
 
 		// 创建Nft艺术品
-		#[weight = 10_000 + T::DbWeight::get().writes(1)]
-		pub fn create(origin, title: Vec<u8>, url: Vec<u8>, desc: Vec<u8>) -> dispatch::DispatchResult {
+		#[weight = 10_000 + T::DbWeight::get().reads_writes(3, 8)]
+		pub fn create(origin, title: Vec<u8>, url: Vec<u8>, desc: Vec<u8>, metadata_hash: [u8; 32], content_type: u8) -> dispatch::DispatchResult {
 			let who = ensure_signed(origin)?;
-			let nft = Nft {
+			// 检查链接长度
+			ensure!(url.len() <= T::MaxUrlLength::get() as usize, Error::<T>::UrlTooLong);
+			// 开启url唯一性检查时, 拒绝已被使用的url
+			let unique_urls = T::UniqueUrls::get();
+			ensure!(!unique_urls || !UrlSeen::get(&url), Error::<T>::DuplicateUrl);
+			let nft = NftMetadata {
 				title,
-				url,
-				desc
+				uri: url,
+				desc,
+				content_type,
+				created_at: frame_system::Module::<T>::block_number(),
 			};
 			NextNftId::<T>::try_mutate(|id| -> DispatchResult {
 				let nft_id = *id;
 				*id = id.checked_add(&One::one()).ok_or(Error::<T>::NftIdOverflow)?;
+				Self::check_id_exhaustion(*id, IdCounter::Nft);
 				// 创建nft并建立 nft索引、账户索引
 				Nfts::<T>::insert(nft_id, &nft);
 				NftAccount::<T>::insert(nft_id, who.clone());
-				Self::deposit_event(RawEvent::NftCreated(who, nft_id));
+				OwnedNfts::<T>::append(&who, nft_id);
+				Self::record_provenance(nft_id, &who);
+				NftMintBlock::<T>::insert(nft_id, nft.created_at);
+				NftHash::<T>::insert(nft_id, metadata_hash);
+				if unique_urls {
+					UrlSeen::insert(&nft.uri, true);
+				}
+				Self::deposit_event(RawEvent::NftCreated(who, nft_id, metadata_hash));
 				Ok(())
 			})?;
 			Ok(())
 		}
 
 		// 移除Nft
-		#[weight = 10_000 + T::DbWeight::get().writes(1)]
+		#[weight = 10_000 + T::DbWeight::get().reads_writes(5, 7)]
 		pub fn remove(origin, nft_id: T::NftId) -> dispatch::DispatchResult {
 			let who = ensure_signed(origin)?;
-			// 检查nft是否存在
-			ensure!(Nfts::<T>::contains_key(&nft_id), Error::<T>::NftIdNotExist);
-
-			let owner = NftAccount::<T>::get(&nft_id);
-			// 检查nft所有者
-			ensure!(owner == who, Error::<T>::NotNftOwner);
+			// 检查nft是否存在及nft所有者
+			Self::ensure_owner(&nft_id, &who)?;
+			// 检查nft是否被冻结
+			ensure!(!FrozenNfts::<T>::get(&nft_id), Error::<T>::NftFrozen);
 			// 检查nft是否处于订单中
 			ensure!(!NftOrder::<T>::contains_key(&nft_id), Error::<T>::NftOrderExist);
 
 			// 移除nft的两个索引
 			NftAccount::<T>::remove(nft_id);
-			Nfts::<T>::remove(nft_id);
+			Self::remove_owned_nft(&who, nft_id);
+			// 清除url记录, 使url可以重新使用
+			if let Some(nft) = Nfts::<T>::take(nft_id) {
+				if T::UniqueUrls::get() {
+					UrlSeen::remove(&nft.uri);
+				}
+			}
+			NftMintBlock::<T>::remove(nft_id);
+			NftHash::<T>::remove(nft_id);
+			BurnedNfts::<T>::insert(nft_id, true);
 
 			Self::deposit_event(RawEvent::NftRemove(who, nft_id));
 			Ok(())
 		}
 
 		// 转移Nft艺术品
-		#[weight = 10_000 + T::DbWeight::get().writes(1)]
+		#[weight = 10_000 + T::DbWeight::get().reads_writes(10, 4)]
 		pub fn transfer(origin, target: T::AccountId, nft_id: T::NftId) -> dispatch::DispatchResult {
 			let who = ensure_signed(origin)?;
-			// 检查nft是否存在
-			ensure!(Nfts::<T>::contains_key(&nft_id), Error::<T>::NftIdNotExist);
+			Self::do_transfer(who, target, nft_id)
+		}
 
-			// 检查nft的所有者
-			let owner = NftAccount::<T>::get(&nft_id);
-			ensure!(owner == who, Error::<T>::NotNftOwner);
+		// 安全转移Nft艺术品, 接收人必须是持有余额的活跃账户
+		#[weight = 10_000 + T::DbWeight::get().reads_writes(11, 4)]
+		pub fn safe_transfer(origin, target: T::AccountId, nft_id: T::NftId) -> dispatch::DispatchResult {
+			let who = ensure_signed(origin)?;
+			// 检查接收人是否为活跃账户, 避免转移到可能被回收的账户
+			ensure!(!T::Currency::free_balance(&target).is_zero(), Error::<T>::RecipientNotLive);
+			Self::do_transfer(who, target, nft_id)
+		}
+
+		// 强制转移Nft, 仅root可以操作, 用于纠纷处理, 不受冻结和铸造锁定期限制
+		#[weight = 10_000 + T::DbWeight::get().reads_writes(5, 4)]
+		pub fn force_transfer(origin, from: T::AccountId, to: T::AccountId, nft_id: T::NftId) -> dispatch::DispatchResult {
+			ensure_root(origin)?;
+			// 检查nft是否存在以及nft的所有者
+			Self::ensure_owner(&nft_id, &from)?;
 
 			// 检查nft是否处于订单中
 			ensure!(!NftOrder::<T>::contains_key(&nft_id), Error::<T>::NftOrderExist);
 
 			// 更改nft账户索引
-			NftAccount::<T>::insert(nft_id, target.clone());
-			Self::deposit_event(RawEvent::NftTransfer(who, target, nft_id));
+			Self::change_nft_owner(nft_id, &to);
+			Self::deposit_event(RawEvent::NftTransfer(from, to, nft_id));
 			Ok(())
 		}
 
-		// 下拍卖单出售艺术品
-		#[weight = 10_000 + T::DbWeight::get().writes(1)]
-		pub fn order_sell(origin, nft_id: T::NftId, start_price: BalanceOf<T>, end_price: BalanceOf<T>, keep_block_num: T::BlockNumber) -> dispatch::DispatchResult {
-			let who = ensure_signed(origin)?;
-			// 检查keep_block_num是否合法
-			ensure!(keep_block_num <= T::MaxKeepBlockNumber::get(), Error::<T>::KeepBlockNumTooBig);
-			ensure!(keep_block_num >= T::MinKeepBlockNumber::get(), Error::<T>::KeepBlockNumTooSmall);
-
+		// 收回已失效账户持有的Nft并转给新的所有者, 仅root可以操作
+		#[weight = 10_000 + T::DbWeight::get().reads_writes(6, 4)]
+		pub fn reclaim_nft(origin, nft_id: T::NftId, new_owner: T::AccountId) -> dispatch::DispatchResult {
+			ensure_root(origin)?;
 			// 检查nft是否存在
 			ensure!(Nfts::<T>::contains_key(&nft_id), Error::<T>::NftIdNotExist);
 
-			// 检查nft的所有者
+			// 检查当前所有者账户是否已经失效(余额为0, 账户已被回收)
 			let owner = NftAccount::<T>::get(&nft_id);
-			ensure!(owner == who, Error::<T>::NotNftOwner);
+			ensure!(T::Currency::total_balance(&owner).is_zero(), Error::<T>::OwnerStillActive);
 
 			// 检查nft是否处于订单中
 			ensure!(!NftOrder::<T>::contains_key(&nft_id), Error::<T>::NftOrderExist);
 
-			// 检查最小价格
-			ensure!(T::MinimumPrice::get() <= start_price, Error::<T>::StartPriceTooLow);
+			Self::change_nft_owner(nft_id, &new_owner);
+			Self::deposit_event(RawEvent::NftTransfer(owner, new_owner, nft_id));
+			Ok(())
+		}
 
-			// 检查价格是否合法
-			ensure!(start_price <= end_price, Error::<T>::OrderPriceIllegal);
+		// 冻结/解冻Nft, 仅root或nft所有者可以操作, root冻结的nft只有root可以解冻
+		#[weight = 10_000 + T::DbWeight::get().reads_writes(3, 2)]
+		pub fn set_frozen(origin, nft_id: T::NftId, frozen: bool) -> dispatch::DispatchResult {
+			let signer = match ensure_root(origin.clone()) {
+				Ok(_) => None,
+				Err(_) => Some(ensure_signed(origin)?),
+			};
+			// 检查nft是否存在, 非root调用时检查nft所有者
+			match &signer {
+				Some(who) => {
+					Self::ensure_owner(&nft_id, who)?;
+					// 所有者不能解除root的冻结, 例如纠纷处理中的冻结
+					ensure!(frozen || !RootFrozenNfts::<T>::get(&nft_id), Error::<T>::FrozenByRoot);
+				},
+				None => ensure!(Nfts::<T>::contains_key(&nft_id), Error::<T>::NftIdNotExist),
+			}
 
-			// 创建订单
-			NextOrderId::<T>::try_mutate(|id| -> DispatchResult {
-				let order_id = *id;
-				let order = Order {
-					order_id,
-					start_price,
-					end_price,
-					nft_id,
-					create_block: frame_system::Module::<T>::block_number(),
-					keep_block_num,
-					owner: who.clone(),
-				};
-				*id = id.checked_add(&One::one()).ok_or(Error::<T>::OrderIdOverflow)?;
-				// 插入订单索引
-				Orders::<T>::insert(order_id, order.clone());
-				NftOrder::<T>::insert(nft_id, order_id);
-				let votes: Vec<VoteOf<T>> = Vec::new();
-				Votes::<T>::insert(order_id, votes);
-				Self::deposit_event(RawEvent::OrderSell(who, order_id));
-				Ok(())
-			})?;
+			if frozen {
+				FrozenNfts::<T>::insert(nft_id, true);
+				if signer.is_none() {
+					RootFrozenNfts::<T>::insert(nft_id, true);
+				}
+			} else {
+				FrozenNfts::<T>::remove(nft_id);
+				RootFrozenNfts::<T>::remove(nft_id);
+			}
+			Self::deposit_event(RawEvent::NftFrozenChanged(nft_id, frozen));
 			Ok(())
 		}
 
-		// 竞拍Nft艺术品
+		// 更新Nft的元数据哈希, 仅nft所有者可以操作
+		#[weight = 10_000 + T::DbWeight::get().reads_writes(3, 1)]
+		pub fn update_hash(origin, nft_id: T::NftId, metadata_hash: [u8; 32]) -> dispatch::DispatchResult {
+			let who = ensure_signed(origin)?;
+			// 检查nft是否存在及nft所有者
+			Self::ensure_owner(&nft_id, &who)?;
+			// 检查nft是否被冻结
+			ensure!(!FrozenNfts::<T>::get(&nft_id), Error::<T>::NftFrozen);
+
+			NftHash::<T>::insert(nft_id, metadata_hash);
+			Self::deposit_event(RawEvent::NftHashUpdated(who, nft_id, metadata_hash));
+			Ok(())
+		}
+
+		// 设置账户是否拒绝接收其他账户转移的nft, 用于防止垃圾nft
 		#[weight = 10_000 + T::DbWeight::get().writes(1)]
-		pub fn order_buy(origin, order_id: T::OrderId, price: BalanceOf<T>) -> dispatch::DispatchResult {
+		pub fn set_transfers_blocked(origin, blocked: bool) -> dispatch::DispatchResult {
 			let who = ensure_signed(origin)?;
+			if blocked {
+				TransfersBlocked::<T>::insert(&who, true);
+			} else {
+				TransfersBlocked::<T>::remove(&who);
+			}
+			Self::deposit_event(RawEvent::TransfersBlockedChanged(who, blocked));
+			Ok(())
+		}
 
-			// 检查订单是否存在
-			let order: OrderOf<T> = Orders::<T>::get(order_id).ok_or(Error::<T>::OrderNotExist)?;
+		// 下拍卖单出售艺术品
+		#[weight = 10_000 + T::DbWeight::get().reads_writes(17, 13)]
+		pub fn order_sell(origin, nft_id: T::NftId, start_price: BalanceOf<T>, end_price: BalanceOf<T>, keep_block_num: T::BlockNumber, params: SellParamsOf<T>) -> dispatch::DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::do_sell(who, vec![nft_id], start_price, end_price, keep_block_num, params)
+		}
 
-			// 检查是否到了结算时间
-			ensure!(!Self::is_time_to_settlement(&order)?, Error::<T>::IsTimeToSettlement);
+		// 打包出售多个Nft艺术品, 以英式拍卖创建一个订单, 成交后所有nft转移给购买者
+		#[weight = 10_000 + T::DbWeight::get().reads_writes(8 + 9 * nft_ids.len() as u64, 11 + 2 * nft_ids.len() as u64)]
+		pub fn bundle_sell(origin, nft_ids: Vec<T::NftId>, start_price: BalanceOf<T>, end_price: BalanceOf<T>, keep_block_num: T::BlockNumber) -> dispatch::DispatchResult {
+			let who = ensure_signed(origin)?;
+			// 打包出售至少包含两个nft, 且不超过上限
+			ensure!(nft_ids.len() >= 2, Error::<T>::BundleTooSmall);
+			ensure!(nft_ids.len() <= T::MaxBundleSize::get() as usize, Error::<T>::BundleTooBig);
+			// 检查是否有重复的nft
+			let mut sorted = nft_ids.clone();
+			sorted.sort();
+			sorted.dedup();
+			ensure!(sorted.len() == nft_ids.len(), Error::<T>::BundleDuplicateNft);
 
-			// 检查最小价格
-			ensure!(T::MinimumPrice::get() <= price, Error::<T>::PriceTooLow);
+			Self::do_sell(who, nft_ids, start_price, end_price, keep_block_num, SellParams::default())
+		}
 
-			// 检查价格是否合法
-			ensure!(order.start_price <= price, Error::<T>::OrderPriceTooSmall);
+		// 竞拍Nft艺术品
+		#[weight = 10_000 + T::DbWeight::get().reads_writes(11, 11)]
+		pub fn order_buy(origin, order_id: T::OrderId, price: BalanceOf<T>, max_price: Option<BalanceOf<T>>) -> dispatch::DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::do_order_buy(&who, order_id, price, max_price)
+		}
 
-			// 检查是否比上个竞价要大
-			let bidopt: Option<BidOf<T>> = Bids::<T>::get(order_id);
-			if let Some(bid) = bidopt {
-				ensure!(bid.price < price, Error::<T>::OrderPriceTooSmall);
-			}
+		// 代理账户以委托人的名义竞拍, 锁定委托人的资金, 竞价和成交的nft都归委托人
+		#[weight = 10_000 + T::DbWeight::get().reads_writes(12, 11)]
+		pub fn delegate_buy(origin, principal: T::AccountId, order_id: T::OrderId, price: BalanceOf<T>, max_price: Option<BalanceOf<T>>) -> dispatch::DispatchResult {
+			let who = ensure_signed(origin)?;
+			// 检查调用者是否为委托人设置的代理账户
+			ensure!(BidDelegates::<T>::get(&principal) == Some(who), Error::<T>::NotBidDelegate);
+			Self::do_order_buy(&principal, order_id, price, max_price)
+		}
 
-			// 检查是否到了最大价格
-			if price >= order.end_price {
-				// 达到最大价格，拍卖成功
-				Self::order_complete(&order, &who, order.end_price, &who)?;
-				// 移除上个bid
-				Self::clean_order_bid(order_id);
-			} else {
-				// 参与竞价
-				// 锁定价格
-				T::Currency::reserve(&who, price)?;
-				// 移除之前的bid
-				Self::clean_order_bid(order_id);
-				// 创建新的bid
-				let bid = Bid {
-					order_id,
-					price,
-					owner: who.clone()
-				};
-				Bids::<T>::insert(order_id, bid.clone());
-				Self::deposit_event(RawEvent::OrderBuy(who, order_id));
+		// 设置或取消竞拍代理账户, 代理账户可以使用本账户的资金竞拍
+		#[weight = 10_000 + T::DbWeight::get().writes(1)]
+		pub fn set_bid_delegate(origin, delegate: Option<T::AccountId>) -> dispatch::DispatchResult {
+			let who = ensure_signed(origin)?;
+			match &delegate {
+				Some(delegate) => BidDelegates::<T>::insert(&who, delegate),
+				None => BidDelegates::<T>::remove(&who),
 			}
+			Self::deposit_event(RawEvent::BidDelegateChanged(who, delegate));
 			Ok(())
 		}
 
 		// 主动结算拍卖 // 用于到期结算
-		#[weight = 10_000 + T::DbWeight::get().writes(1)]
-		pub fn order_settlement(origin, order_id: T::OrderId) -> dispatch::DispatchResult {
+		#[weight = 10_000 + T::DbWeight::get().reads_writes(20, 25)]
+		pub fn order_settlement(origin, order_id: T::OrderId) -> dispatch::DispatchResultWithPostInfo {
 			let who = ensure_signed(origin)?;
+			// 提前返回时只读取了暂停开关和订单, 按实际权重收费, 避免轮询结算的账户被多收手续费
+			let early_weight = T::DbWeight::get().reads(2);
+			// 检查交易是否被暂停
+			ensure!(!Paused::get(), Error::<T>::Paused.with_weight(early_weight));
 			// 检查订单是否存在
-			let order: OrderOf<T> = Orders::<T>::get(order_id).ok_or(Error::<T>::OrderNotExist)?;
+			let order: OrderOf<T> = Orders::<T>::get(order_id).ok_or_else(|| Error::<T>::OrderNotExist.with_weight(early_weight))?;
 			// 检查是否可以进行结算订单
-			ensure!(Self::is_time_to_settlement(&order)?, Error::<T>::IsNotTimeToSettlement);
-
-			// 获取最后那个竞价
-			let bidopt: Option<BidOf<T>> = Bids::<T>::get(order_id);
-			if let Some(bid) = bidopt {
-				// 移除之前的bid
-				Self::clean_order_bid(order_id);
-				Self::order_complete(&order, &bid.owner, bid.price, &who)?;
-				Self::deposit_event(RawEvent::OrderComplete(bid.owner, order_id));
-			} else {
-				// 移除订单索引
-				Orders::<T>::remove(order_id);
-				NftOrder::<T>::remove(order.nft_id);
-				let votes: Vec<VoteOf<T>> = Votes::<T>::get(order_id);
-				for vote in votes {
-					T::Currency::unreserve(&vote.owner, vote.amount);
+			ensure!(Self::is_time_to_settlement(&order), Error::<T>::IsNotTimeToSettlement.with_weight(early_weight));
+			// 检查订单是否有冷静期内的一口价购买
+			ensure!(!PendingBuyouts::<T>::contains_key(order_id), Error::<T>::BuyoutPending);
+
+			Self::settle_order(&order, Some(&who))?;
+			Ok(().into())
+		}
+
+		// 批量结算到期的订单, 未到结算时间或结算失败的订单直接跳过
+		#[weight = 10_000 + T::DbWeight::get().reads(1) + (order_ids.len() as Weight).saturating_mul(10_000 + T::DbWeight::get().reads_writes(20, 25))]
+		pub fn order_settlement_batch(origin, order_ids: Vec<T::OrderId>) -> dispatch::DispatchResult {
+			let who = ensure_signed(origin)?;
+			// 检查交易是否被暂停
+			ensure!(!Paused::get(), Error::<T>::Paused);
+			// 检查批量结算的订单数量
+			ensure!(order_ids.len() <= T::MaxSettlementBatch::get() as usize, Error::<T>::SettlementBatchTooLarge);
+
+			let mut settled: u32 = 0;
+			for order_id in order_ids.iter() {
+				let order: OrderOf<T> = match Orders::<T>::get(order_id) {
+					Some(order) => order,
+					None => continue,
+				};
+				// 冷静期内的一口价购买不能结算
+				if !Self::is_time_to_settlement(&order) || PendingBuyouts::<T>::contains_key(order_id) {
+					continue;
+				}
+				// settle_order失败时回滚该订单的修改, 不影响其他订单
+				match Self::settle_order(&order, Some(&who)) {
+					Ok(()) => settled += 1,
+					Err(e) => debug::warn!("=>批量结算订单失败: {:?}, {:?}", order_id, e),
 				}
-				Votes::<T>::remove(order_id);
-				Self::deposit_event(RawEvent::OrderCancel(order.owner, order_id));
 			}
+			let skipped = (order_ids.len() as u32).saturating_sub(settled);
+			Self::deposit_event(RawEvent::SettlementBatch(who, settled, skipped));
+			Ok(())
+		}
+
+		// 买家在冷静期内确认一口价购买, 立即成交
+		#[weight = 10_000 + T::DbWeight::get().reads_writes(21, 25)]
+		pub fn confirm_buyout(origin, order_id: T::OrderId) -> dispatch::DispatchResult {
+			let who = ensure_signed(origin)?;
+			// 检查交易是否被暂停
+			ensure!(!Paused::get(), Error::<T>::Paused);
+			// 检查是否有冷静期内的一口价购买
+			let (buyer, _, _) = PendingBuyouts::<T>::get(order_id).ok_or(Error::<T>::NoPendingBuyout)?;
+			// 检查调用者是否为买家
+			ensure!(buyer == who, Error::<T>::NotBuyoutBuyer);
+
+			Self::finalize_buyout(order_id)
+		}
+
+		// 买家在冷静期内取消一口价购买, 按BuyoutCancelPenalty向卖家支付违约金, 订单恢复拍卖
+		#[weight = 10_000 + T::DbWeight::get().reads_writes(7, 6)]
+		pub fn cancel_buyout(origin, order_id: T::OrderId) -> dispatch::DispatchResult {
+			let who = ensure_signed(origin)?;
+			// 检查交易是否被暂停
+			ensure!(!Paused::get(), Error::<T>::Paused);
+			// 检查是否有冷静期内的一口价购买
+			let (buyer, price, _) = PendingBuyouts::<T>::get(order_id).ok_or(Error::<T>::NoPendingBuyout)?;
+			// 检查调用者是否为买家
+			ensure!(buyer == who, Error::<T>::NotBuyoutBuyer);
+			let order: OrderOf<T> = Orders::<T>::get(order_id).ok_or(Error::<T>::OrderNotExist)?;
+
+			let penalty = T::BuyoutCancelPenalty::get().mul_floor(price);
+			Self::abandon_buyout(&order, &who, price, penalty)?;
+			Self::deposit_event(RawEvent::BuyoutCancelled(who, order_id, penalty));
+			Ok(())
+		}
+
+		// 卖家在结算前撤销订单, 已有竞价时按CancelPenalty向最高出价者支付补偿, 否则向国库账户支付CancellationFee
+		#[weight = 10_000 + T::DbWeight::get().reads_writes(14, 19)]
+		#[transactional]
+		pub fn cancel_order(origin, order_id: T::OrderId) -> dispatch::DispatchResult {
+			let who = ensure_signed(origin)?;
+			// 检查交易是否被暂停
+			ensure!(!Paused::get(), Error::<T>::Paused);
+			// 检查订单是否存在
+			let order: OrderOf<T> = Orders::<T>::get(order_id).ok_or(Error::<T>::OrderNotExist)?;
+			// 检查订单所有者
+			ensure!(order.owner == who, Error::<T>::NotNftOwner);
+			// 检查是否到了结算时间
+			ensure!(!Self::is_time_to_settlement(&order), Error::<T>::IsTimeToSettlement);
+			// 检查订单是否有冷静期内的一口价购买
+			ensure!(!PendingBuyouts::<T>::contains_key(order_id), Error::<T>::BuyoutPending);
+
+			let mut deposit = order.deposit;
+			let bid = Bids::<T>::get(order_id);
+			if let Some(bid) = &bid {
+				deposit = Self::pay_cancel_penalty(&order, bid)?;
+			}
+			Self::clean_order_bid(order_id);
+			Self::remove_order(&order);
+			let votes: Vec<VoteOf<T>> = Votes::<T>::take(order_id);
+			Self::refund_votes(order_id, &votes);
+			Self::unreserve_funds(&who, deposit);
+			// 没有竞价时收取撤单手续费, 挂单押金已经退还, 可以用于支付
+			let fee = T::CancellationFee::get();
+			if bid.is_none() && !fee.is_zero() {
+				T::PaymentCurrency::transfer(&who, &T::TreasuryAccount::get(), fee, ExistenceRequirement::KeepAlive)
+					.map_err(|_| Error::<T>::CancellationFeeUnaffordable)?;
+			}
+			Self::deposit_event(RawEvent::OrderCancel(who, order_id));
 			Ok(())
 		}
 
+		// 转让订单的卖家权利(成交所得和挂单押金), 清空原卖家设置的收款账户, nft和竞价保持不变
+		#[weight = 10_000 + T::DbWeight::get().reads_writes(10, 8)]
+		#[transactional]
+		pub fn transfer_order_ownership(origin, order_id: T::OrderId, new_owner: T::AccountId) -> dispatch::DispatchResult {
+			let who = ensure_signed(origin)?;
+			// 检查交易是否被暂停
+			ensure!(!Paused::get(), Error::<T>::Paused);
+			// 检查订单是否存在
+			let mut order: OrderOf<T> = Orders::<T>::get(order_id).ok_or(Error::<T>::OrderNotExist)?;
+			// 检查订单所有者
+			ensure!(order.owner == who, Error::<T>::NotNftOwner);
+			// 检查是否到了结算时间
+			ensure!(!Self::is_time_to_settlement(&order), Error::<T>::IsTimeToSettlement);
+			// 检查订单是否有冷静期内的一口价购买
+			ensure!(!PendingBuyouts::<T>::contains_key(order_id), Error::<T>::BuyoutPending);
+			// 检查新卖家的订单数
+			ensure!(OrderCountByOwner::<T>::get(&new_owner) < T::MaxOrdersPerAccount::get(), Error::<T>::TooManyOrders);
+
+			// 挂单押金随订单转给新卖家, 保持锁定
+			if !order.deposit.is_zero() {
+				let reserved = Self::reserved_after(&new_owner, order.deposit)?;
+				let remaining = T::PaymentCurrency::repatriate_reserved(&who, &new_owner, order.deposit, BalanceStatus::Reserved)?;
+				ensure!(remaining.is_zero(), Error::<T>::InsufficientReservedBalance);
+				Self::sub_reserved(&who, order.deposit);
+				Self::add_reserved(&new_owner, reserved, order.deposit);
+			}
+			OrderCountByOwner::<T>::mutate_exists(&who, |count| {
+				*count = count.map(|c| c.saturating_sub(1)).filter(|c| *c > 0);
+			});
+			OrderCountByOwner::<T>::mutate(&new_owner, |count| *count += 1);
+			// 原卖家设置的收款账户随卖家权利一并失效, 成交所得全部归新卖家
+			order.owner = new_owner.clone();
+			order.payees = Vec::new();
+			Orders::<T>::insert(order_id, order);
+			Self::deposit_event(RawEvent::OrderOwnershipTransferred(order_id, who, new_owner));
+			Ok(())
+		}
+
+		// 卖家在没有竞价时修改订单价格
+		#[weight = 10_000 + T::DbWeight::get().reads_writes(4, 1)]
+		pub fn update_order_price(origin, order_id: T::OrderId, new_start: BalanceOf<T>, new_end: BalanceOf<T>) -> dispatch::DispatchResult {
+			let who = ensure_signed(origin)?;
+			// 检查交易是否被暂停
+			ensure!(!Paused::get(), Error::<T>::Paused);
+			// 检查订单是否存在
+			let mut order: OrderOf<T> = Orders::<T>::get(order_id).ok_or(Error::<T>::OrderNotExist)?;
+			// 检查订单所有者
+			ensure!(order.owner == who, Error::<T>::NotNftOwner);
+			// 检查是否到了结算时间
+			ensure!(!Self::is_time_to_settlement(&order), Error::<T>::IsTimeToSettlement);
+			// 检查订单是否有冷静期内的一口价购买
+			ensure!(!PendingBuyouts::<T>::contains_key(order_id), Error::<T>::BuyoutPending);
+			// 已有竞价时不允许修改价格
+			ensure!(!Bids::<T>::contains_key(order_id), Error::<T>::OrderHasBid);
+
+			// 重新检查价格
+			ensure!(!new_start.is_zero(), Error::<T>::OrderPriceTooSmall);
+			ensure!(T::MinimumPrice::get() <= new_start, Error::<T>::StartPriceTooLow);
+			ensure!(new_start <= new_end, Error::<T>::OrderPriceIllegal);
+			ensure!(new_end <= T::MaxOrderPrice::get(), Error::<T>::OrderPriceTooBig);
+			ensure!(Self::is_on_tick(new_start) && Self::is_on_tick(new_end), Error::<T>::PriceNotOnTick);
+
+			order.start_price = new_start;
+			order.end_price = new_end;
+			Orders::<T>::insert(order_id, order);
+			Self::deposit_event(RawEvent::OrderPriceUpdated(who, order_id, new_start, new_end));
+			Ok(())
+		}
+
+		// 卖家在到期前延长拍卖时间
+		// 已有竞价时也允许延期, 竞价者的资金会继续锁定到新的到期区块
+		#[weight = 10_000 + T::DbWeight::get().reads_writes(4, 3)]
+		pub fn extend_order(origin, order_id: T::OrderId, additional_blocks: T::BlockNumber) -> dispatch::DispatchResult {
+			let who = ensure_signed(origin)?;
+			// 检查交易是否被暂停
+			ensure!(!Paused::get(), Error::<T>::Paused);
+			// 检查订单是否存在
+			let mut order: OrderOf<T> = Orders::<T>::get(order_id).ok_or(Error::<T>::OrderNotExist)?;
+			// 检查订单所有者
+			ensure!(order.owner == who, Error::<T>::NotNftOwner);
+			// 检查是否到了结算时间
+			ensure!(!Self::is_time_to_settlement(&order), Error::<T>::IsTimeToSettlement);
+			// 检查订单是否有冷静期内的一口价购买
+			ensure!(!PendingBuyouts::<T>::contains_key(order_id), Error::<T>::BuyoutPending);
+
+			Self::do_extend_order(&mut order, additional_blocks, &who)
+		}
+
 		// 进行投票质押
-		#[weight = 10_000 + T::DbWeight::get().writes(1)]
+		#[weight = 10_000 + T::DbWeight::get().reads_writes(8, 6)]
 		pub fn vote_order(origin, order_id: T::OrderId, amount: BalanceOf<T>) -> dispatch::DispatchResult {
 			let who = ensure_signed(origin)?;
+			// 检查交易是否被暂停
+			ensure!(!Paused::get(), Error::<T>::Paused);
 			// 检查订单是否存在
 			let order: OrderOf<T> = Orders::<T>::get(order_id).ok_or(Error::<T>::OrderNotExist)?;
 
 			// 检查是否到了结算时间
-			ensure!(!Self::is_time_to_settlement(&order)?, Error::<T>::IsTimeToSettlement);
+			ensure!(!Self::is_time_to_settlement(&order), Error::<T>::IsTimeToSettlement);
+
+			// 检查订单是否已经开始
+			ensure!(Self::is_started(&order), Error::<T>::OrderNotStarted);
+
+			// 检查订单是否有冷静期内的一口价购买
+			ensure!(!PendingBuyouts::<T>::contains_key(order_id), Error::<T>::BuyoutPending);
+
+			// 检查卖家是否可以质押自己的订单
+			ensure!(T::AllowSellerVote::get() || who != order.owner, Error::<T>::SellerCannotVote);
 
 			// 检查最小质押
 			ensure!(T::MinimumVotingLock::get() <= amount, Error::<T>::VoteAmountTooLow);
 
+			// 检查质押后可用余额不低于最小存款, 避免账户被回收
+			ensure!(
+				T::PaymentCurrency::free_balance(&who).saturating_sub(amount) >= T::PaymentCurrency::minimum_balance(),
+				Error::<T>::InsufficientBalanceForVote
+			);
+
 			let now = frame_system::Module::<T>::block_number();
-			let keep_block_num = order.create_block
-				.checked_add(&order.keep_block_num).ok_or(Error::<T>::BlockNumberOverflow)?
-				.checked_sub(&now).ok_or(Error::<T>::BlockNumberOverflow)?;
+			// 当前区块已超过到期区块说明拍卖已经结束
+			let keep_block_num = order.expire_block.checked_sub(&now).ok_or(Error::<T>::IsTimeToSettlement)?;
 
-			// 质押
-			T::Currency::reserve(&who, amount)?;
-			// 插入投票信息
-			Votes::<T>::try_mutate(order_id, |votes| -> DispatchResult {
-				let vote = Vote {
+			// 临近到期时不允许质押
+			ensure!(keep_block_num >= T::MinVoteLockBlocks::get(), Error::<T>::VoteWindowClosed);
+
+			// 检查订单的质押总额
+			let vote_total = OrderVoteTotal::<T>::get(order_id).checked_add(&amount).ok_or(Error::<T>::OrderVoteCapReached)?;
+			ensure!(vote_total <= T::MaxTotalVotesPerOrder::get(), Error::<T>::OrderVoteCapReached);
+
+			// 插入投票信息, 同一账户重复质押时合并为一条记录
+			let mut votes: Vec<VoteOf<T>> = Votes::<T>::get(order_id);
+			if let Some(vote) = votes.iter_mut().find(|v| v.owner == who) {
+				vote.keep_block_num = Self::merge_keep_block_num(vote, amount, keep_block_num);
+				vote.amount = vote.amount.checked_add(&amount).ok_or(Error::<T>::VoteAmountOverflow)?;
+			} else {
+				votes.push(Vote {
 					order_id,
 					amount,
 					keep_block_num,
 					owner: who.clone()
-				};
-				votes.push(vote);
-				Ok(())
-			})?;
+				});
+			}
+
+			// 质押
+			Self::reserve_funds(&who, amount, Error::<T>::InsufficientBalanceForVote)?;
+			OrderVoteTotal::<T>::insert(order_id, vote_total);
+			VoterCount::<T>::insert(order_id, votes.len() as u32);
+			Votes::<T>::insert(order_id, votes);
+			Self::deposit_event(RawEvent::VotePlaced(who, order_id, amount, keep_block_num));
 			Ok(())
 		}
-	}
-}
 
-impl<T: Trait> Module<T> {
+		// 减少质押, 减少到0时移除质押记录
+		#[weight = 10_000 + T::DbWeight::get().reads_writes(8, 6)]
+		pub fn reduce_vote(origin, order_id: T::OrderId, amount: BalanceOf<T>) -> dispatch::DispatchResult {
+			let who = ensure_signed(origin)?;
+			// 检查交易是否被暂停
+			ensure!(!Paused::get(), Error::<T>::Paused);
+			// 检查订单是否存在
+			let order: OrderOf<T> = Orders::<T>::get(order_id).ok_or(Error::<T>::OrderNotExist)?;
 
-	// 清理bid的reserve，和索引
-	pub fn clean_order_bid(order_id: T::OrderId) {
-		let bid_opt: Option<BidOf<T>> = Bids::<T>::get(order_id);
-		if let Some(bid) = bid_opt {
-			// 解锁之前的锁定的钱
-			T::Currency::unreserve(&bid.owner, bid.price);
-			Bids::<T>::remove(order_id);
+			// 检查是否到了结算时间
+			ensure!(!Self::is_time_to_settlement(&order), Error::<T>::IsTimeToSettlement);
+
+			// 冷静期内质押者不能退出, 等待购买确认或取消
+			ensure!(!PendingBuyouts::<T>::contains_key(order_id), Error::<T>::BuyoutPending);
+
+			let mut votes: Vec<VoteOf<T>> = Votes::<T>::get(order_id);
+			let index = votes.iter().position(|v| v.owner == who).ok_or(Error::<T>::ReduceTooLarge)?;
+			let remaining = votes[index].amount.checked_sub(&amount).ok_or(Error::<T>::ReduceTooLarge)?;
+			if remaining.is_zero() {
+				votes.remove(index);
+			} else {
+				// 剩余质押不能低于最小质押
+				ensure!(T::MinimumVotingLock::get() <= remaining, Error::<T>::VoteAmountTooLow);
+				votes[index].amount = remaining;
+			}
+
+			Self::unreserve_funds(&who, amount);
+			OrderVoteTotal::<T>::mutate(order_id, |total| *total = total.saturating_sub(amount));
+			VoterCount::<T>::insert(order_id, votes.len() as u32);
+			Votes::<T>::insert(order_id, votes);
+			Ok(())
 		}
-	}
 
-	// 需要在Order里面增加创建订单时的区块，根据order中的keep_block_number设置检查是否到期
-	// 到期则返回true，否则返回false
-	fn is_time_to_settlement(order: &OrderOf<T>) -> Result<bool, DispatchError> {
-		let now = frame_system::Module::<T>::block_number();
-		let sub_block = now.checked_sub(&order.create_block).ok_or(Error::<T>::BlockNumberOverflow)?;
-		Ok(sub_block > order.keep_block_num)
-	}
+		// 解锁提前成交后继续锁定的质押, 只能在订单原定的到期区块之后调用
+		#[weight = 10_000 + T::DbWeight::get().reads_writes(5, 4)]
+		pub fn release_vote(origin, order_id: T::OrderId) -> dispatch::DispatchResult {
+			let who = ensure_signed(origin)?;
+			// 检查交易是否被暂停
+			ensure!(!Paused::get(), Error::<T>::Paused);
+			// 检查是否有继续锁定的质押
+			let (amount, unlock_block) = LockedVotes::<T>::get(order_id, &who).ok_or(Error::<T>::NoLockedVote)?;
+			// 检查质押是否已经到期
+			ensure!(frame_system::Module::<T>::block_number() >= unlock_block, Error::<T>::VoteStillLocked);
 
+			LockedVotes::<T>::remove(order_id, &who);
+			Self::unreserve_funds(&who, amount);
+			Self::deposit_event(RawEvent::VoteRefunded(who, order_id, amount));
+			Ok(())
+		}
 
-	fn order_complete(
-		order: &OrderOf<T>,
-		bid: &T::AccountId, // 购买者
-		price: BalanceOf<T>, // 最终购买价格
-		_settlement: &T::AccountId // 触发完成人
-	) -> dispatch::DispatchResult {
-		T::Currency::transfer(
-			&bid, &order.owner, price, ExistenceRequirement::KeepAlive
-		)?;
-		// 移除订单索引
-		Orders::<T>::remove(order.order_id);
-		NftOrder::<T>::remove(order.nft_id);
-		let votes: Vec<VoteOf<T>> = Votes::<T>::get(order.order_id);
-		Self::algorithm(&order, price, votes.clone());
-		Votes::<T>::remove(order.order_id);
-		// 更新nft账户索引
-		NftAccount::<T>::insert(order.nft_id, bid.clone());
-		Self::deposit_event(RawEvent::OrderComplete(bid.clone(), order.order_id));
-		Ok(())
-	}
+		// 领取从托管账户退还失败的出价, 例如竞价者账户已被回收且退还金额低于最低存在余额时
+		#[weight = 10_000 + T::DbWeight::get().reads_writes(6, 5)]
+		pub fn claim_bid_refund(origin, order_id: T::OrderId) -> dispatch::DispatchResult {
+			let who = ensure_signed(origin)?;
+			// 检查交易是否被暂停
+			ensure!(!Paused::get(), Error::<T>::Paused);
+			// 检查是否有等待领取的出价
+			let amount = PendingBidRefunds::<T>::get(order_id, &who);
+			ensure!(!amount.is_zero(), Error::<T>::NoBidRefund);
 
-	pub fn algorithm(
-		order: &OrderOf<T>, // 最大拍卖区块数
-		bid_price: BalanceOf<T>, // 购买价格
-		inputs: Vec<VoteOf<T>> //质押列表
-	) {
-		if inputs.is_empty() {
-			return
+			T::PaymentCurrency::transfer(&Self::escrow_account(order_id), &who, amount, ExistenceRequirement::AllowDeath)?;
+			PendingBidRefunds::<T>::remove(order_id, &who);
+			Self::sub_reserved(&who, amount);
+			Self::deposit_event(RawEvent::BidRefundClaimed(who, order_id, amount));
+			Ok(())
 		}
-		let fix_rate: U64F64 = U64F64::from_num(T::FixRate::get());
-		let profit_rate: U64F64 = U64F64::from_num(T::ProfitRate::get());
-		let day_block_num: u128 = T::DayBlockNum::get().saturated_into();
-		let day_block_num: U64F64 = U64F64::from_num(day_block_num);
-		let block_num: u128 = order.keep_block_num.saturated_into();
-		let block_num: U64F64 = U64F64::from_num(block_num);
-		let bid_price: u128 = bid_price.saturated_into();
-		let bid_price: U64F64 = U64F64::from_num(bid_price);
 
-		let day: U64F64 = block_num / day_block_num;
-		let stock: U64F64 = bid_price * profit_rate / day * U64F64::from_num(365); // 初始股权数
+		// 领取nft分红
+		#[weight = 10_000 + T::DbWeight::get().reads_writes(5, 4)]
+		pub fn claim_dividend(origin, nft_id: T::NftId) -> dispatch::DispatchResult {
+			let who = ensure_signed(origin)?;
+			// 检查交易是否被暂停
+			ensure!(!Paused::get(), Error::<T>::Paused);
+			let amount = Dividends::<T>::get(nft_id, &who);
+			ensure!(!amount.is_zero(), Error::<T>::NoDividend);
 
-		debug::warn!(
-			"=>当前价格为: {}, 分成比例为: {}%, 拍卖时长: {}day, 初始股权数: {}, 固定年化: {}%",
-			bid_price,
-			profit_rate,
-			day,
-			stock,
-			fix_rate
-		);
+			T::PaymentCurrency::transfer(&T::DividendAccount::get(), &who, amount, ExistenceRequirement::KeepAlive)?;
+			Dividends::<T>::remove(nft_id, &who);
+			DividendPool::<T>::mutate(nft_id, |pool| *pool = pool.saturating_sub(amount));
+			Self::deposit_event(RawEvent::DividendClaimed(who, nft_id, amount));
+			Ok(())
+		}
 
-		let mut is_fixed: bool = false; // 是否开启固定利率
-		let mut total: U64F64 = U64F64::from_num(0.0); // 总质押数量
+		// 授权或取消授权操作员转移和出售自己的全部nft
+		#[weight = 10_000 + T::DbWeight::get().writes(1)]
+		pub fn set_approval_for_all(origin, operator: T::AccountId, approved: bool) -> dispatch::DispatchResult {
+			let who = ensure_signed(origin)?;
+			if approved {
+				OperatorApprovals::<T>::insert(&who, &operator, true);
+			} else {
+				OperatorApprovals::<T>::remove(&who, &operator);
+			}
+			Self::deposit_event(RawEvent::ApprovalForAll(who, operator, approved));
+			Ok(())
+		}
+
+		// 设置紧急暂停开关, 仅root可以操作, 暂停期间nft的移除和转移不受影响
+		#[weight = 10_000 + T::DbWeight::get().writes(1)]
+		pub fn set_paused(origin, paused: bool) -> dispatch::DispatchResult {
+			ensure_root(origin)?;
+			Paused::put(paused);
+			Self::deposit_event(RawEvent::PausedChanged(paused));
+			Ok(())
+		}
+
+		// 创建nft合集
+		#[weight = 10_000 + T::DbWeight::get().reads_writes(1, 2)]
+		pub fn create_collection(origin) -> dispatch::DispatchResult {
+			let who = ensure_signed(origin)?;
+			let collection_id = NextCollectionId::get();
+			let next = collection_id.checked_add(1).ok_or(Error::<T>::CollectionIdOverflow)?;
+			Collections::<T>::insert(collection_id, Collection { owner: who.clone(), closed: false });
+			NextCollectionId::put(next);
+			Self::deposit_event(RawEvent::CollectionCreated(who, collection_id));
+			Ok(())
+		}
+
+		// 将自己的nft加入自己的合集, 每个nft最多属于一个合集
+		#[weight = 10_000 + T::DbWeight::get().reads_writes(5, 2)]
+		pub fn add_to_collection(origin, collection_id: CollectionId, nft_id: T::NftId) -> dispatch::DispatchResult {
+			let who = ensure_signed(origin)?;
+			// 检查合集是否存在及合集所有者
+			let collection = Collections::<T>::get(collection_id).ok_or(Error::<T>::CollectionNotExist)?;
+			ensure!(collection.owner == who, Error::<T>::NotCollectionOwner);
+			ensure!(!collection.closed, Error::<T>::CollectionClosed);
+			// 检查nft是否存在及nft所有者
+			Self::ensure_owner(&nft_id, &who)?;
+			ensure!(!NftCollection::<T>::contains_key(nft_id), Error::<T>::NftInCollection);
+
+			CollectionNfts::<T>::try_mutate(collection_id, |nfts| -> DispatchResult {
+				ensure!(nfts.len() < T::MaxCollectionSize::get() as usize, Error::<T>::CollectionFull);
+				nfts.push(nft_id);
+				Ok(())
+			})?;
+			NftCollection::<T>::insert(nft_id, collection_id);
+			Self::deposit_event(RawEvent::CollectionNftAdded(collection_id, nft_id));
+			Ok(())
+		}
+
+		// 关闭合集: 撤销合集中nft的所有订单(退还竞价、质押和挂单押金), 之后合集中的nft不能再挂单
+		// 每次最多处理CollectionCloseBatch个nft, 未处理完时再次调用从上次的位置继续
+		#[weight = 10_000 + T::DbWeight::get().reads_writes(4, 2)
+			+ (T::CollectionCloseBatch::get() as Weight).saturating_mul(10_000 + T::DbWeight::get().reads_writes(14, 19))]
+		#[transactional]
+		pub fn close_collection(origin, collection_id: CollectionId) -> dispatch::DispatchResult {
+			let who = ensure_signed(origin)?;
+			// 检查交易是否被暂停
+			ensure!(!Paused::get(), Error::<T>::Paused);
+			// 检查合集是否存在及合集所有者
+			let mut collection = Collections::<T>::get(collection_id).ok_or(Error::<T>::CollectionNotExist)?;
+			ensure!(collection.owner == who, Error::<T>::NotCollectionOwner);
+			// 第一次调用时标记关闭, 之后从上次处理的位置继续
+			let start = match CollectionCloseCursor::get(collection_id) {
+				Some(cursor) => cursor,
+				None => {
+					ensure!(!collection.closed, Error::<T>::CollectionAlreadyClosed);
+					collection.closed = true;
+					Collections::<T>::insert(collection_id, collection);
+					0
+				},
+			};
+
+			let nfts = CollectionNfts::<T>::get(collection_id);
+			let end = nfts.len().min(start as usize + T::CollectionCloseBatch::get() as usize);
+			for nft_id in nfts.iter().take(end).skip(start as usize) {
+				let order = NftOrder::<T>::get(nft_id).and_then(|order_id| Orders::<T>::get(order_id));
+				if let Some(order) = order {
+					Self::unwind_order(&order);
+				}
+			}
+
+			if end < nfts.len() {
+				CollectionCloseCursor::insert(collection_id, end as u32);
+				Self::deposit_event(RawEvent::CollectionClosing(collection_id, end as u32));
+			} else {
+				CollectionCloseCursor::remove(collection_id);
+				Self::deposit_event(RawEvent::CollectionClosed(collection_id));
+			}
+			Ok(())
+		}
+	}
+}
+
+impl<T: Trait> Module<T> {
+
+	// 将Nfts中旧版本的nft详情迁移为NftMetadata, 铸造区块取自NftMintBlock, 内容类型为0
+	// 已经是新格式的数据保持不变, 因此重复执行是安全的
+	fn migrate_nft_metadata() -> Weight {
+		let nft_ids: Vec<T::NftId> = NftAccount::<T>::iter().map(|(nft_id, _)| nft_id).collect();
+		let mut migrated: Weight = 0;
+		for nft_id in nft_ids.iter() {
+			let key = Nfts::<T>::hashed_key_for(nft_id);
+			let raw = match frame_support::storage::unhashed::get_raw(&key) {
+				Some(raw) => raw,
+				None => continue,
+			};
+			if NftMetadataOf::<T>::decode_all(&raw).is_ok() {
+				continue;
+			}
+			if let Ok(old) = Nft::decode_all(&raw) {
+				Nfts::<T>::insert(nft_id, NftMetadata {
+					title: old.title,
+					uri: old.url,
+					desc: old.desc,
+					content_type: 0,
+					created_at: NftMintBlock::<T>::get(nft_id),
+				});
+				migrated += 1;
+			}
+		}
+		T::DbWeight::get().reads_writes(2 * nft_ids.len() as Weight + 1, migrated)
+	}
+
+	// 将Orders中初始版本的订单迁移为当前格式, 到期区块为create_block + keep_block_num, 其余新增字段取默认值
+	// 同时补齐订单索引, 已经到期的订单在下一个区块自动结算; 已经是新格式的订单保持不变, 因此重复执行是安全的
+	fn migrate_legacy_orders() -> Weight {
+		let mut order_ids: Vec<T::OrderId> = NftOrder::<T>::iter().map(|(_, order_id)| order_id).collect();
+		order_ids.sort();
+		order_ids.dedup();
+		let now = frame_system::Module::<T>::block_number();
+		let mut migrated: Weight = 0;
+		let mut backfilled: Weight = 0;
+		for order_id in order_ids.iter() {
+			let key = Orders::<T>::hashed_key_for(order_id);
+			let raw = match frame_support::storage::unhashed::get_raw(&key) {
+				Some(raw) => raw,
+				None => continue,
+			};
+			if OrderOf::<T>::decode_all(&raw).is_ok() {
+				continue;
+			}
+			if let Ok(old) = LegacyOrderOf::<T>::decode_all(&raw) {
+				let expire_block = old.create_block.saturating_add(old.keep_block_num);
+				let settle_block = expire_block.max(now).saturating_add(One::one());
+				ActiveOrders::<T>::insert(order_id, ());
+				OrderCountByOwner::<T>::mutate(&old.owner, |count| *count += 1);
+				ExpiringOrders::<T>::append(settle_block, order_id);
+				Orders::<T>::insert(order_id, Order {
+					order_id: old.order_id,
+					start_price: old.start_price,
+					end_price: old.end_price,
+					nft_id: old.nft_id,
+					create_block: old.create_block,
+					start_block: old.create_block,
+					keep_block_num: old.keep_block_num,
+					owner: old.owner,
+					expire_block,
+					kind: AuctionKind::English,
+					auto_relist: false,
+					relist_count: 0,
+					deposit: Zero::zero(),
+					fee_rate: None,
+					settlement_mode: SettlementMode::FirstPrice,
+					payees: Vec::new(),
+				});
+				// 旧版本没有锁定资金统计, 补记已有出价和质押
+				let mut locked: Vec<(T::AccountId, BalanceOf<T>)> = Votes::<T>::get(order_id).into_iter()
+					.map(|vote| (vote.owner, vote.amount))
+					.collect();
+				if let Some(bid) = Bids::<T>::get(order_id) {
+					locked.push((bid.owner, bid.price));
+				}
+				for (who, amount) in locked.iter() {
+					ReservedByAccount::<T>::mutate(who, |reserved| *reserved = reserved.saturating_add(*amount));
+					TotalReserved::<T>::mutate(|total| *total = total.saturating_add(*amount));
+				}
+				backfilled += locked.len() as Weight;
+				migrated += 1;
+			}
+		}
+		T::DbWeight::get().reads_writes(
+			2 * order_ids.len() as Weight + 3 * migrated + 2 * backfilled + 1,
+			4 * migrated + 2 * backfilled,
+		)
+	}
+
+	// 更新nft所有者, 同时维护账户持有nft索引
+	fn change_nft_owner(nft_id: T::NftId, to: &T::AccountId) {
+		let from = NftAccount::<T>::get(nft_id);
+		Self::remove_owned_nft(&from, nft_id);
+		NftAccount::<T>::insert(nft_id, to.clone());
+		OwnedNfts::<T>::append(to, nft_id);
+		Self::record_provenance(nft_id, to);
+	}
+
+	// 记录nft的所有权历史, 超出上限时丢弃最早的记录
+	fn record_provenance(nft_id: T::NftId, owner: &T::AccountId) {
+		let now = frame_system::Module::<T>::block_number();
+		OwnershipHistory::<T>::mutate(nft_id, |history| {
+			history.push((owner.clone(), now));
+			let max = T::MaxProvenanceEntries::get() as usize;
+			if history.len() > max {
+				let overflow = history.len() - max;
+				history.drain(..overflow);
+			}
+		});
+	}
+
+	// 创建拍卖订单, nft_ids包含多个nft时为打包出售, 第一个nft作为订单的nft_id, 分红股份记在该nft上
+	#[transactional]
+	fn do_sell(
+		who: T::AccountId,
+		nft_ids: Vec<T::NftId>,
+		start_price: BalanceOf<T>,
+		end_price: BalanceOf<T>,
+		keep_block_num: T::BlockNumber,
+		params: SellParamsOf<T>,
+	) -> dispatch::DispatchResult {
+		let owner = Self::check_sell(&who, &nft_ids, start_price, end_price, keep_block_num, &params)?;
+
+		// 从nft所有者锁定挂单押金
+		let deposit = T::ListingDeposit::get();
+		Self::reserve_funds(&owner, deposit, Error::<T>::InsufficientBalanceForDeposit)?;
+
+		OrdersThisBlock::mutate(|count| *count += 1);
+		for nft_id in nft_ids.iter() {
+			NftListCount::<T>::mutate(nft_id, |count| *count = count.saturating_add(1));
+		}
+
+		// 创建订单, 订单Id和区块信息在create_order中填充
+		let bundle = if nft_ids.len() > 1 { nft_ids.clone() } else { Vec::new() };
+		Self::create_order(Order {
+			order_id: Default::default(),
+			start_price,
+			end_price,
+			nft_id: nft_ids[0],
+			create_block: Zero::zero(),
+			start_block: Zero::zero(),
+			keep_block_num,
+			owner,
+			expire_block: Zero::zero(),
+			kind: params.kind,
+			auto_relist: params.auto_relist,
+			relist_count: 0,
+			deposit,
+			fee_rate: params.fee_rate,
+			settlement_mode: params.settlement_mode,
+			payees: params.payees,
+		}, params.start_delay.unwrap_or_else(Zero::zero), bundle)?;
+		Ok(())
+	}
+
+	// 挂单前的所有检查, 不修改状态, 返回订单所属的nft所有者
+	fn check_sell(
+		who: &T::AccountId,
+		nft_ids: &[T::NftId],
+		start_price: BalanceOf<T>,
+		end_price: BalanceOf<T>,
+		keep_block_num: T::BlockNumber,
+		params: &SellParamsOf<T>,
+	) -> Result<T::AccountId, DispatchError> {
+		// 检查交易是否被暂停
+		ensure!(!Paused::get(), Error::<T>::Paused);
+		// 检查keep_block_num是否合法
+		ensure!(keep_block_num <= T::MaxKeepBlockNumber::get(), Error::<T>::KeepBlockNumTooBig);
+		ensure!(keep_block_num >= T::MinKeepBlockNumber::get(), Error::<T>::KeepBlockNumTooSmall);
+		// 无论最小保留区块数如何配置, 拍卖时长都不能为0, 否则订单创建后立即可以结算
+		ensure!(keep_block_num > Zero::zero(), Error::<T>::KeepBlockNumTooSmall);
+
+		// 订单属于nft所有者, 调用者可以是所有者授权的操作员, 打包出售的nft必须属于同一所有者
+		let owner = NftAccount::<T>::get(&nft_ids[0]);
+
+		// 检查每个nft的状态
+		for nft_id in nft_ids.iter() {
+			// 检查nft是否已被销毁
+			ensure!(!BurnedNfts::<T>::get(nft_id), Error::<T>::NftBurned);
+
+			// 检查nft是否存在及nft的所有者, 打包出售的nft必须属于同一所有者
+			ensure!(Self::ensure_owner_or_operator(nft_id, who)? == owner, Error::<T>::NotNftOwner);
+
+			// 检查nft是否被冻结
+			ensure!(!FrozenNfts::<T>::get(nft_id), Error::<T>::NftFrozen);
+
+			// 检查nft是否在铸造锁定期内
+			ensure!(!Self::is_mint_locked(*nft_id), Error::<T>::NftLocked);
+
+			// 检查nft是否在成交后的再次挂单冷却期内
+			ensure!(!Self::is_relist_cooling(*nft_id), Error::<T>::RelistCooldownActive);
+
+			// 检查nft是否处于订单中
+			ensure!(!NftOrder::<T>::contains_key(nft_id), Error::<T>::NftOrderExist);
+
+			// 检查nft所属的合集是否已关闭
+			ensure!(!Self::is_collection_closed(*nft_id), Error::<T>::CollectionClosed);
+
+			// 检查nft的挂单次数
+			ensure!(NftListCount::<T>::get(nft_id) < T::MaxListingsPerNft::get(), Error::<T>::ListingLimitReached);
+		}
+
+		// 起拍价格不能为0, 否则分润算法中的初始股权数为0
+		ensure!(!start_price.is_zero(), Error::<T>::OrderPriceTooSmall);
+
+		// 检查最小价格
+		ensure!(T::MinimumPrice::get() <= start_price, Error::<T>::StartPriceTooLow);
+
+		// 检查价格是否合法
+		ensure!(start_price <= end_price, Error::<T>::OrderPriceIllegal);
+
+		// 检查当前区块的新建订单数
+		ensure!(OrdersThisBlock::get() < T::MaxOrdersPerBlock::get(), Error::<T>::BlockOrderLimitReached);
+
+		// 检查账户的订单数
+		ensure!(OrderCountByOwner::<T>::get(&owner) < T::MaxOrdersPerAccount::get(), Error::<T>::TooManyOrders);
+
+		// 检查最大价格
+		ensure!(end_price <= T::MaxOrderPrice::get(), Error::<T>::OrderPriceTooBig);
+
+		// 检查价格是否为最小变动单位的整数倍
+		ensure!(Self::is_on_tick(start_price) && Self::is_on_tick(end_price), Error::<T>::PriceNotOnTick);
+
+		// 第二价格结算只适用于英式拍卖
+		ensure!(params.settlement_mode == SettlementMode::FirstPrice || params.kind == AuctionKind::English, Error::<T>::SecondPriceNotEnglish);
+
+		// 设置收款账户时比例之和必须为100%
+		if !params.payees.is_empty() {
+			let total: u32 = params.payees.iter().map(|(_, rate)| rate.deconstruct()).sum();
+			ensure!(total == Permill::one().deconstruct(), Error::<T>::PayeeSplitInvalid);
+		}
+
+		// 只有管理员可以设置低于默认比例的手续费
+		if let Some(rate) = params.fee_rate {
+			ensure!(rate >= T::PlatformFeeRate::get() || *who == T::FeeAdmin::get(), Error::<T>::FeeRateNotAllowed);
+			// 手续费与其他扣除比例之和不能超过100%, 否则成交时卖家所得不足以支付分红和分润
+			ensure!(Self::deductions_within_price(rate), Error::<T>::FeeRateTooHigh);
+		}
+		Ok(owner)
+	}
+
+	// 成交价中按比例扣除的手续费、结算奖励、分红和质押者分润之和是否不超过100%
+	fn deductions_within_price(fee_rate: Permill) -> bool {
+		let accuracy = Permill::ACCURACY as u64;
+		let profit_parts = (T::ProfitRate::get() * accuracy as f64) as u64;
+		let total = [fee_rate, T::SettlementReward::get(), T::DividendRate::get()].iter()
+			.map(|rate| rate.deconstruct() as u64)
+			.sum::<u64>()
+			.saturating_add(profit_parts);
+		total <= accuracy
+	}
+
+	// 竞拍订单, who为竞价者, 锁定其资金并在成交时获得nft
+	fn do_order_buy(who: &T::AccountId, order_id: T::OrderId, price: BalanceOf<T>, max_price: Option<BalanceOf<T>>) -> dispatch::DispatchResult {
+		let order = Self::check_buy(order_id, price, max_price)?;
+
+		match order.kind {
+			AuctionKind::English => {
+				let bidopt: Option<BidOf<T>> = Bids::<T>::get(order_id);
+				// 检查是否到了最大价格
+				if price >= order.end_price {
+					// 达到最大价格，拍卖成功
+					Self::buy_now(&order, who, order.end_price)?;
+				} else {
+					// 参与竞价
+					Self::place_bid(order, who, price, bidopt)?;
+				}
+			},
+			AuctionKind::Dutch => {
+				// 出价不低于当前价格时以当前价格成交
+				let current_price = Self::dutch_price(&order);
+				Self::buy_now(&order, who, current_price)?;
+			},
+			AuctionKind::FixedPrice => {
+				// 一口价订单只能以起拍价格成交
+				Self::buy_now(&order, who, order.start_price)?;
+			},
+		}
+		Ok(())
+	}
+
+	// 竞拍前的所有检查, 不修改状态, 返回订单
+	fn check_buy(order_id: T::OrderId, price: BalanceOf<T>, max_price: Option<BalanceOf<T>>) -> Result<OrderOf<T>, DispatchError> {
+		// 检查交易是否被暂停
+		ensure!(!Paused::get(), Error::<T>::Paused);
+
+		// 检查订单是否存在
+		let order: OrderOf<T> = Orders::<T>::get(order_id).ok_or(Error::<T>::OrderNotExist)?;
+
+		// 检查是否到了结算时间
+		ensure!(!Self::is_time_to_settlement(&order), Error::<T>::IsTimeToSettlement);
+
+		// 检查订单是否已经开始
+		ensure!(Self::is_started(&order), Error::<T>::OrderNotStarted);
+
+		// 检查订单是否有冷静期内的一口价购买
+		ensure!(!PendingBuyouts::<T>::contains_key(order_id), Error::<T>::BuyoutPending);
+
+		// 检查订单的nft是否仍然存在
+		ensure!(Self::order_nfts(&order).iter().all(|nft_id| Nfts::<T>::contains_key(nft_id)), Error::<T>::NftIdNotExist);
+
+		// 检查最小价格
+		ensure!(T::MinimumPrice::get() <= price, Error::<T>::PriceTooLow);
+
+		// 检查价格是否合法
+		ensure!(order.start_price <= price, Error::<T>::BelowStartPrice);
+
+		// 检查出价是否为最小变动单位的整数倍
+		ensure!(Self::is_on_tick(price), Error::<T>::PriceNotOnTick);
+
+		// 滑点保护: 当前价格超过买家可接受的最高价格时拒绝
+		if let Some(max_price) = max_price {
+			ensure!(Self::current_price(&order) <= max_price, Error::<T>::PriceExceedsMax);
+		}
+
+		match order.kind {
+			AuctionKind::English => {
+				// 检查是否比上个竞价要大
+				if let Some(bid) = Bids::<T>::get(order_id) {
+					ensure!(bid.price < price, Error::<T>::BelowCurrentBid);
+				}
+			},
+			AuctionKind::Dutch => {
+				// 出价不能低于当前价格
+				ensure!(Self::dutch_price(&order) <= price, Error::<T>::OrderPriceTooSmall);
+			},
+			AuctionKind::FixedPrice => {
+				// 一口价订单不允许竞价
+				ensure!(price == order.start_price, Error::<T>::BiddingNotAllowed);
+			},
+		}
+		Ok(order)
+	}
+
+	// 试运行挂单检查, 不修改状态, 返回挂单是否会成功或具体的错误
+	pub fn validate_order_sell(
+		who: T::AccountId,
+		nft_id: T::NftId,
+		start_price: BalanceOf<T>,
+		end_price: BalanceOf<T>,
+		keep_block_num: T::BlockNumber,
+		params: SellParamsOf<T>,
+	) -> Result<(), DispatchError> {
+		Self::check_sell(&who, &[nft_id], start_price, end_price, keep_block_num, &params).map(|_| ())
+	}
+
+	// 试运行竞拍检查, 不修改状态, 返回竞拍是否会成功或具体的错误
+	pub fn validate_order_buy(order_id: T::OrderId, price: BalanceOf<T>, max_price: Option<BalanceOf<T>>) -> Result<(), DispatchError> {
+		Self::check_buy(order_id, price, max_price).map(|_| ())
+	}
+
+	fn do_transfer(who: T::AccountId, target: T::AccountId, nft_id: T::NftId) -> dispatch::DispatchResult {
+		// 检查nft是否已被销毁
+		ensure!(!BurnedNfts::<T>::get(&nft_id), Error::<T>::NftBurned);
+
+		// 检查nft是否存在及nft的所有者, 被授权的操作员也可以转移
+		let owner = Self::ensure_owner_or_operator(&nft_id, &who)?;
+
+		// 检查nft是否被冻结
+		ensure!(!FrozenNfts::<T>::get(&nft_id), Error::<T>::NftFrozen);
+
+		// 检查nft是否在铸造锁定期内
+		ensure!(!Self::is_mint_locked(nft_id), Error::<T>::NftLocked);
+
+		// 检查nft是否处于订单中
+		ensure!(!NftOrder::<T>::contains_key(&nft_id), Error::<T>::NftOrderExist);
+
+		// 检查接收人是否拒绝接收转移
+		ensure!(target == owner || !TransfersBlocked::<T>::get(&target), Error::<T>::RecipientBlocksTransfers);
+
+		// 更改nft账户索引
+		Self::change_nft_owner(nft_id, &target);
+		Self::deposit_event(RawEvent::NftTransfer(owner, target, nft_id));
+		Ok(())
+	}
+
+	// 价格是否为最小变动单位的整数倍, 最小变动单位为0时不限制
+	fn is_on_tick(price: BalanceOf<T>) -> bool {
+		let tick = T::PriceTick::get();
+		tick.is_zero() || (price % tick).is_zero()
+	}
+
+	// 调用者是nft所有者, 或者是所有者授权的操作员
+	fn is_owner_or_operator(owner: &T::AccountId, who: &T::AccountId) -> bool {
+		owner == who || OperatorApprovals::<T>::get(owner, who)
+	}
+
+	// 检查nft存在且who为nft所有者
+	fn ensure_owner(nft_id: &T::NftId, who: &T::AccountId) -> DispatchResult {
+		ensure!(Nfts::<T>::contains_key(nft_id), Error::<T>::NftIdNotExist);
+		ensure!(NftAccount::<T>::get(nft_id) == *who, Error::<T>::NotNftOwner);
+		Ok(())
+	}
+
+	// 检查nft存在且who为nft所有者或其授权的操作员, 返回nft所有者
+	// Nfts和NftAccount两个索引不一致时视为nft不存在, 避免在残缺的状态上挂单或转移
+	fn ensure_owner_or_operator(nft_id: &T::NftId, who: &T::AccountId) -> Result<T::AccountId, DispatchError> {
+		ensure!(Nfts::<T>::contains_key(nft_id), Error::<T>::NftIdNotExist);
+		ensure!(NftAccount::<T>::contains_key(nft_id), Error::<T>::NftIdNotExist);
+		let owner = NftAccount::<T>::get(nft_id);
+		ensure!(Self::is_owner_or_operator(&owner, who), Error::<T>::NotNftOwner);
+		Ok(owner)
+	}
+
+	// nft所属的合集是否已关闭
+	fn is_collection_closed(nft_id: T::NftId) -> bool {
+		NftCollection::<T>::get(nft_id)
+			.and_then(|collection_id| Collections::<T>::get(collection_id))
+			.map_or(false, |collection| collection.closed)
+	}
+
+	// nft是否在成交后的再次挂单冷却期内
+	fn is_relist_cooling(nft_id: T::NftId) -> bool {
+		match LastSoldBlock::<T>::get(nft_id) {
+			Some(sold) => frame_system::Module::<T>::block_number() < sold.saturating_add(T::RelistCooldown::get()),
+			None => false,
+		}
+	}
+
+	// 检查nft是否还在铸造锁定期内
+	fn is_mint_locked(nft_id: T::NftId) -> bool {
+		let unlock_block = NftMintBlock::<T>::get(nft_id).saturating_add(T::MintLockPeriod::get());
+		frame_system::Module::<T>::block_number() < unlock_block
+	}
+
+	// 从账户持有nft索引中移除nft
+	fn remove_owned_nft(who: &T::AccountId, nft_id: T::NftId) {
+		OwnedNfts::<T>::mutate_exists(who, |ids| {
+			if let Some(list) = ids {
+				list.retain(|id| *id != nft_id);
+				if list.is_empty() {
+					*ids = None;
+				}
+			}
+		});
+	}
+
+	// 以指定价格直接成交: 先锁定成交价格, 成交时直接转移锁定的资金
+	#[transactional]
+	fn buy_now(order: &OrderOf<T>, who: &T::AccountId, price: BalanceOf<T>) -> dispatch::DispatchResult {
+		Self::ensure_bid_affordable(order.order_id, who, price)?;
+		// 先退还上个bid, 再成交, order_complete不应看到任何竞价
+		Self::clean_order_bid(order.order_id);
+		debug_assert!(!Bids::<T>::contains_key(order.order_id));
+		let cooldown = T::BuyoutCooldown::get();
+		let finalize_block = frame_system::Module::<T>::block_number().checked_add(&cooldown).ok_or(Error::<T>::BlockNumberOverflow)?;
+		Self::lock_bid(order.order_id, who, price)?;
+		Self::deposit_event(RawEvent::OrderBuyNow(who.clone(), order.order_id, price));
+		if cooldown.is_zero() {
+			return Self::order_complete(order, who, price, None);
+		}
+		// 进入冷静期, 资金保持锁定, 冷静期结束后自动成交
+		PendingBuyouts::<T>::insert(order.order_id, (who.clone(), price, finalize_block));
+		ExpiringBuyouts::<T>::append(finalize_block, order.order_id);
+		Self::deposit_event(RawEvent::BuyoutPending(who.clone(), order.order_id, finalize_block));
+		Ok(())
+	}
+
+	// 确认冷静期内的一口价购买并成交
+	#[transactional]
+	fn finalize_buyout(order_id: T::OrderId) -> dispatch::DispatchResult {
+		let (buyer, price, _) = PendingBuyouts::<T>::take(order_id).ok_or(Error::<T>::NoPendingBuyout)?;
+		let order: OrderOf<T> = Orders::<T>::get(order_id).ok_or(Error::<T>::OrderNotExist)?;
+		Self::order_complete(&order, &buyer, price, None)
+	}
+
+	// 结束冷静期内的一口价购买而不成交: 违约金支付给卖家, 其余资金退还买家, 订单恢复拍卖
+	// 订单已经到期时重新加入自动结算索引
+	fn abandon_buyout(order: &OrderOf<T>, buyer: &T::AccountId, price: BalanceOf<T>, penalty: BalanceOf<T>) -> dispatch::DispatchResult {
+		Self::repatriate_bid(order.order_id, buyer, &order.owner, penalty)?;
+		Self::release_bid(order.order_id, buyer, price.saturating_sub(penalty));
+		PendingBuyouts::<T>::remove(order.order_id);
+		if Self::is_time_to_settlement(order) {
+			let now = frame_system::Module::<T>::block_number();
+			ExpiringOrders::<T>::append(now.saturating_add(One::one()), order.order_id);
+		}
+		Ok(())
+	}
+
+	// 卖家撤销订单时, 按最高出价的CancelPenalty比例向最高出价者支付补偿, 返回剩余的挂单押金
+	// 补偿优先从挂单押金中扣除, 不足部分从卖家的可用余额中支付
+	fn pay_cancel_penalty(order: &OrderOf<T>, bid: &BidOf<T>) -> Result<BalanceOf<T>, DispatchError> {
+		let penalty = T::CancelPenalty::get().mul_floor(bid.price);
+		let from_deposit = penalty.min(order.deposit);
+		let from_free = penalty.saturating_sub(from_deposit);
+		ensure!(T::PaymentCurrency::free_balance(&order.owner) >= from_free, Error::<T>::CancelPenaltyUnaffordable);
+		if !from_deposit.is_zero() {
+			let remaining = T::PaymentCurrency::repatriate_reserved(&order.owner, &bid.owner, from_deposit, BalanceStatus::Free)?;
+			ensure!(remaining.is_zero(), Error::<T>::CancelPenaltyUnaffordable);
+			Self::sub_reserved(&order.owner, from_deposit);
+		}
+		if !from_free.is_zero() {
+			T::PaymentCurrency::transfer(&order.owner, &bid.owner, from_free, ExistenceRequirement::KeepAlive)
+				.map_err(|_| Error::<T>::CancelPenaltyUnaffordable)?;
+		}
+		Self::deposit_event(RawEvent::CancelPenaltyPaid(order.owner.clone(), order.order_id, bid.owner.clone(), penalty));
+		Ok(order.deposit.saturating_sub(from_deposit))
+	}
+
+	// 自动确认冷静期在当前区块结束的一口价购买, 成交失败时全额退还买家
+	fn finalize_expired_buyouts(now: T::BlockNumber) -> Weight {
+		let order_ids: Vec<T::OrderId> = ExpiringBuyouts::<T>::take(now);
+		for order_id in order_ids.iter() {
+			if let Err(e) = Self::finalize_buyout(*order_id) {
+				debug::warn!("=>一口价购买自动成交失败: {:?}, {:?}", order_id, e);
+				if let (Some((buyer, price, _)), Some(order)) = (PendingBuyouts::<T>::get(order_id), Orders::<T>::get(order_id)) {
+					let _ = Self::abandon_buyout(&order, &buyer, price, Zero::zero());
+				}
+			}
+		}
+		T::DbWeight::get().reads(1)
+			.saturating_add((order_ids.len() as Weight).saturating_mul(10_000 + T::DbWeight::get().reads_writes(20, 25)))
+	}
+
+	// 订单当前价格: 英式拍卖为当前最高出价(没有出价时为起拍价格), 荷兰式拍卖为递减后的价格, 一口价为起拍价格
+	pub fn current_price(order: &OrderOf<T>) -> BalanceOf<T> {
+		match order.kind {
+			AuctionKind::English => Bids::<T>::get(order.order_id).map(|bid| bid.price).unwrap_or(order.start_price),
+			AuctionKind::Dutch => Self::dutch_price(order),
+			AuctionKind::FixedPrice => order.start_price,
+		}
+	}
+
+	// 荷兰式拍卖的当前价格, 从结拍价格按已经过的区块数线性递减到起拍价格
+	pub fn dutch_price(order: &OrderOf<T>) -> BalanceOf<T> {
+		let now = frame_system::Module::<T>::block_number();
+		let elapsed: u128 = now.saturating_sub(order.start_block).saturated_into();
+		let keep: u128 = order.keep_block_num.saturated_into();
+		if keep == 0 || elapsed >= keep {
+			return order.start_price;
+		}
+		let range: u128 = order.end_price.saturating_sub(order.start_price).saturated_into();
+		let decayed = multiply_by_rational(range, elapsed, keep).unwrap_or(range);
+		let decayed: BalanceOf<T> = decayed.saturated_into();
+		order.end_price.saturating_sub(decayed).max(order.start_price)
+	}
+
+	// 锁定出价并记录为当前最高出价, 在防狙击窗口内出价时延长拍卖时间
+	#[transactional]
+	fn place_bid(mut order: OrderOf<T>, who: &T::AccountId, price: BalanceOf<T>, prev_bid: Option<BidOf<T>>) -> dispatch::DispatchResult {
+		let order_id = order.order_id;
+		Self::ensure_bid_affordable(order_id, who, price)?;
+		match prev_bid {
+			// 当前最高竞价者加价, 只需要锁定差额
+			Some(prev) if prev.owner == *who => {
+				Self::lock_bid(order_id, who, price.saturating_sub(prev.price))?;
+			},
+			_ => {
+				// 新的最高出价者之前的候补出价先退还
+				Self::remove_queued_bid(order_id, who);
+				// 锁定价格
+				Self::lock_bid(order_id, who, price)?;
+				// 之前的最高出价进入候补队列
+				if let Some(prev) = Bids::<T>::take(order_id) {
+					Self::queue_bid(prev, Self::queue_capacity(&order));
+				}
+			},
+		}
+		// 创建新的bid
+		let bid = Bid {
+			order_id,
+			price,
+			owner: who.clone()
+		};
+		Bids::<T>::insert(order_id, bid);
+		Self::deposit_event(RawEvent::OrderBuy(who.clone(), order_id));
+
+		// 记录出价历史, 超出上限时丢弃最早的记录
+		let now = frame_system::Module::<T>::block_number();
+		BidHistory::<T>::mutate(order_id, |history| {
+			history.push((who.clone(), price, now));
+			let max = T::MaxBidHistory::get() as usize;
+			if history.len() > max {
+				let overflow = history.len() - max;
+				history.drain(..overflow);
+			}
+		});
+
+		// 剩余区块数不足防狙击窗口时延长到窗口大小, 但不超过最大保留区块数
+		let remaining = order.expire_block.saturating_sub(now);
+		let window = T::AntiSnipeWindow::get();
+		if remaining < window {
+			let max_extension = T::MaxKeepBlockNumber::get().saturating_sub(order.keep_block_num);
+			let extension = (window - remaining).min(max_extension);
+			if !extension.is_zero() {
+				Self::do_extend_order(&mut order, extension, who)?;
+			}
+		}
+		Ok(())
+	}
+
+	// 订单的托管账户
+	pub fn escrow_account(order_id: T::OrderId) -> T::AccountId {
+		T::EscrowModuleId::get().into_sub_account(order_id)
+	}
+
+	// 锁定竞价资金: 使用托管账户时转入订单的托管账户, 否则锁定在竞价者账户中
+	fn lock_bid(order_id: T::OrderId, who: &T::AccountId, amount: BalanceOf<T>) -> dispatch::DispatchResult {
+		if !T::UseEscrow::get() {
+			return Self::reserve_funds(who, amount, Error::<T>::InsufficientBalanceForBid);
+		}
+		let reserved = Self::reserved_after(who, amount)?;
+		let escrow = Self::escrow_account(order_id);
+		// 托管账户不存在时, 转入的金额必须足以创建账户
+		ensure!(
+			!T::PaymentCurrency::total_balance(&escrow).is_zero() || amount >= T::PaymentCurrency::minimum_balance(),
+			Error::<T>::BidBelowExistentialDeposit
+		);
+		// 与reserve一致, 竞价者可以锁定全部可用余额
+		T::PaymentCurrency::transfer(who, &escrow, amount, ExistenceRequirement::AllowDeath)
+			.map_err(|_| Error::<T>::InsufficientBalanceForBid)?;
+		Self::add_reserved(who, reserved, amount);
+		Ok(())
+	}
+
+	// 退还竞价资金: 使用托管账户时从订单的托管账户转回竞价者, 否则解锁
+	// 转账失败时记入PendingBidRefunds, 由竞价者调用claim_bid_refund领取, 资金仍计入锁定总额
+	fn release_bid(order_id: T::OrderId, who: &T::AccountId, amount: BalanceOf<T>) {
+		if !T::UseEscrow::get() {
+			return Self::unreserve_funds(who, amount);
+		}
+		if amount.is_zero() {
+			return;
+		}
+		match T::PaymentCurrency::transfer(&Self::escrow_account(order_id), who, amount, ExistenceRequirement::AllowDeath) {
+			Ok(()) => Self::sub_reserved(who, amount),
+			Err(e) => {
+				debug::warn!("=>托管账户退还竞价失败, 等待竞价者领取: {:?}, {:?}, {:?}", order_id, who, e);
+				PendingBidRefunds::<T>::mutate(order_id, who, |pending| *pending = pending.saturating_add(amount));
+				Self::deposit_event(RawEvent::BidRefundPending(who.clone(), order_id, amount));
+			},
+		}
+	}
+
+	// 将购买者锁定的资金转移到收款账户的可用余额, 使用托管账户时从订单的托管账户转出
+	fn repatriate_bid(order_id: T::OrderId, bid: &T::AccountId, dest: &T::AccountId, amount: BalanceOf<T>) -> dispatch::DispatchResult {
+		if T::UseEscrow::get() {
+			T::PaymentCurrency::transfer(&Self::escrow_account(order_id), dest, amount, ExistenceRequirement::AllowDeath)?;
+		} else {
+			let remaining = T::PaymentCurrency::repatriate_reserved(bid, dest, amount, BalanceStatus::Free)?;
+			ensure!(remaining.is_zero(), Error::<T>::InsufficientReservedBalance);
+		}
+		Self::sub_reserved(bid, amount);
+		Ok(())
+	}
+
+	// 锁定出价或质押资金, 并计入锁定总额和账户锁定总额, 账户锁定总额不能超过MaxReservedPerAccount
+	// 余额不足无法锁定时返回insufficient, 代替货币模块的通用错误
+	fn reserve_funds(who: &T::AccountId, amount: BalanceOf<T>, insufficient: Error<T>) -> dispatch::DispatchResult {
+		let reserved = Self::reserved_after(who, amount)?;
+		T::PaymentCurrency::reserve(who, amount).map_err(|_| insufficient)?;
+		Self::add_reserved(who, reserved, amount);
+		Ok(())
+	}
+
+	// 锁定amount后账户的锁定总额, 不能超过MaxReservedPerAccount
+	fn reserved_after(who: &T::AccountId, amount: BalanceOf<T>) -> Result<BalanceOf<T>, DispatchError> {
+		let reserved = ReservedByAccount::<T>::get(who).checked_add(&amount).ok_or(Error::<T>::ReservedLimitExceeded)?;
+		ensure!(reserved <= T::MaxReservedPerAccount::get(), Error::<T>::ReservedLimitExceeded);
+		Ok(reserved)
+	}
+
+	// 记录账户新的锁定总额, 并计入所有订单的锁定总额
+	fn add_reserved(who: &T::AccountId, reserved: BalanceOf<T>, amount: BalanceOf<T>) {
+		ReservedByAccount::<T>::insert(who, reserved);
+		TotalReserved::<T>::mutate(|total| *total = total.saturating_add(amount));
+	}
+
+	// 从锁定总额和账户锁定总额中扣除已经解锁或转移的资金
+	fn sub_reserved(who: &T::AccountId, amount: BalanceOf<T>) {
+		TotalReserved::<T>::mutate(|total| *total = total.saturating_sub(amount));
+		ReservedByAccount::<T>::mutate_exists(who, |reserved| {
+			let remaining = reserved.unwrap_or_else(Zero::zero).saturating_sub(amount);
+			*reserved = if remaining.is_zero() { None } else { Some(remaining) };
+		});
+	}
+
+	// 解锁出价或质押资金, 并从锁定总额中扣除实际解锁的数量
+	fn unreserve_funds(who: &T::AccountId, amount: BalanceOf<T>) {
+		let remaining = T::PaymentCurrency::unreserve(who, amount);
+		let unreserved = amount.saturating_sub(remaining);
+		Self::sub_reserved(who, unreserved);
+	}
+
+	// 罚没锁定的资金并存入国库账户, 国库账户不存在时自动创建, 不会销毁资金
+	fn slash_to_treasury(who: &T::AccountId, amount: BalanceOf<T>) {
+		let (imbalance, remaining) = T::PaymentCurrency::slash_reserved(who, amount);
+		T::PaymentCurrency::resolve_creating(&T::TreasuryAccount::get(), imbalance);
+		let slashed = amount.saturating_sub(remaining);
+		Self::sub_reserved(who, slashed);
+	}
+
+	// 清理bid的reserve，和索引
+	pub fn clean_order_bid(order_id: T::OrderId) {
+		let bid_opt: Option<BidOf<T>> = Bids::<T>::get(order_id);
+		if let Some(bid) = bid_opt {
+			// 解锁之前的锁定的钱
+			Self::release_bid(order_id, &bid.owner, bid.price);
+			Bids::<T>::remove(order_id);
+		}
+		// 退还所有候补出价
+		for bid in BidQueue::<T>::take(order_id) {
+			Self::release_bid(order_id, &bid.owner, bid.price);
+		}
+	}
+
+	// 订单候补队列的容量, 第二价格结算需要至少保留次高出价
+	fn queue_capacity(order: &OrderOf<T>) -> usize {
+		let max = T::MaxQueuedBids::get() as usize;
+		match order.settlement_mode {
+			SettlementMode::FirstPrice => max,
+			SettlementMode::SecondPrice => max.max(1),
+		}
+	}
+
+	// 被超过的出价放入候补队列头部(队列中价格最高), 超出容量的最低出价被退还
+	fn queue_bid(bid: BidOf<T>, max: usize) {
+		BidQueue::<T>::mutate(bid.order_id, |queue| {
+			queue.insert(0, bid);
+			while queue.len() > max {
+				if let Some(evicted) = queue.pop() {
+					Self::release_bid(evicted.order_id, &evicted.owner, evicted.price);
+				}
+			}
+		});
+	}
+
+	// 修改状态前检查竞价者的可用余额是否足以锁定price, 本人已有的最高出价或候补出价会先退还, 只需要差额
+	fn ensure_bid_affordable(order_id: T::OrderId, who: &T::AccountId, price: BalanceOf<T>) -> dispatch::DispatchResult {
+		let own_bid = Bids::<T>::get(order_id).filter(|bid| bid.owner == *who)
+			.or_else(|| BidQueue::<T>::get(order_id).into_iter().find(|bid| bid.owner == *who))
+			.map(|bid| bid.price)
+			.unwrap_or_else(Zero::zero);
+		let required = price.saturating_sub(own_bid);
+		ensure!(T::PaymentCurrency::free_balance(who) >= required, Error::<T>::InsufficientBalanceForBid);
+		Ok(())
+	}
+
+	// 移除并退还账户在候补队列中的出价
+	fn remove_queued_bid(order_id: T::OrderId, who: &T::AccountId) {
+		BidQueue::<T>::mutate(order_id, |queue| {
+			if let Some(index) = queue.iter().position(|bid| bid.owner == *who) {
+				let bid = queue.remove(index);
+				Self::release_bid(order_id, &bid.owner, bid.price);
+			}
+		});
+	}
+
+	// 结算到期订单: 有竞价则成交, 否则取消订单并解锁质押
+	// settlement为手动结算人, 自动结算时为None
+	#[transactional]
+	fn settle_order(order: &OrderOf<T>, settlement: Option<&T::AccountId>) -> dispatch::DispatchResult {
+		let order_id = order.order_id;
+		// 卖家结算自己的订单时不奖励, 避免卖家反复挂单流拍后自行结算领取平台奖励
+		let settlement = settlement.filter(|settler| **settler != order.owner);
+		// 获取最后那个竞价
+		let bidopt: Option<BidOf<T>> = Bids::<T>::get(order_id);
+		if let Some(bid) = bidopt {
+			// 移除之前的bid, 锁定的竞价资金在order_complete中直接转给卖家
+			Bids::<T>::remove(order_id);
+			let mut candidates = vec![bid];
+			candidates.extend(BidQueue::<T>::take(order_id));
+			// 依次尝试由最高出价者成交, 失败时退还其出价并由次高的候补出价者成交
+			let mut last_err = None;
+			while !candidates.is_empty() {
+				let candidate = candidates.remove(0);
+				let price = Self::clearing_price(order, &candidate, candidates.first());
+				match Self::order_complete(&order, &candidate.owner, price, settlement) {
+					Ok(()) => {
+						// 第二价格结算时退还出价与成交价的差额
+						Self::release_bid(order_id, &candidate.owner, candidate.price.saturating_sub(price));
+						last_err = None;
+						break;
+					},
+					Err(e) => {
+						debug::warn!("=>订单成交失败, 尝试候补出价: {:?}, {:?}, {:?}", order_id, candidate.owner, e);
+						Self::release_bid(order_id, &candidate.owner, candidate.price);
+						last_err = Some(e);
+					},
+				}
+			}
+			if let Some(e) = last_err {
+				return Err(e);
+			}
+			// 成交后退还剩余的候补出价
+			for bid in candidates {
+				Self::release_bid(order_id, &bid.owner, bid.price);
+			}
+		} else {
+			// 移除订单索引
+			let bundle = BundleOrders::<T>::get(order_id);
+			Self::remove_order(order);
+			let votes: Vec<VoteOf<T>> = Votes::<T>::take(order_id);
+			Self::refund_votes(order_id, &votes);
+			Self::pay_voter_consolation(order, &votes);
+			// 由平台账户奖励手动结算人, 平台账户余额不足时跳过
+			let reward = T::CancelSettlementReward::get();
+			if let (Some(settler), false) = (settlement, reward.is_zero()) {
+				let _ = T::PaymentCurrency::transfer(&T::FeeAccount::get(), settler, reward, ExistenceRequirement::KeepAlive);
+			}
+			// 开启自动重新挂单且未超过次数上限时, 以相同参数创建新订单, 押金继续锁定
+			// 未开启、达到上限或重新挂单的检查失败时取消订单并退还押金, 不影响本次结算
+			let relisted = order.auto_relist && order.relist_count < T::MaxAutoRelists::get() && match Self::relist_order(order, bundle) {
+				Ok(()) => true,
+				Err(e) => {
+					debug::warn!("=>自动重新挂单失败: {:?}, {:?}", order_id, e);
+					false
+				},
+			};
+			if !relisted {
+				Self::unreserve_funds(&order.owner, order.deposit);
+				Self::deposit_event(RawEvent::OrderCancel(order.owner.clone(), order_id));
+			}
+		}
+		Ok(())
+	}
+
+	// 流拍后以相同参数重新挂单, 与order_sell执行相同的检查并计入挂单次数, 订单必须仍属于nft所有者
+	#[transactional]
+	fn relist_order(order: &OrderOf<T>, bundle: Vec<T::NftId>) -> dispatch::DispatchResult {
+		let nft_ids = if bundle.is_empty() { vec![order.nft_id] } else { bundle.clone() };
+		let params = SellParams {
+			kind: order.kind,
+			auto_relist: order.auto_relist,
+			start_delay: None,
+			fee_rate: order.fee_rate,
+			settlement_mode: order.settlement_mode,
+			payees: order.payees.clone(),
+		};
+		let owner = Self::check_sell(&order.owner, &nft_ids, order.start_price, order.end_price, order.keep_block_num, &params)?;
+		ensure!(owner == order.owner, Error::<T>::NotNftOwner);
+
+		OrdersThisBlock::mutate(|count| *count += 1);
+		for nft_id in nft_ids.iter() {
+			NftListCount::<T>::mutate(nft_id, |count| *count = count.saturating_add(1));
+		}
+		let mut relisted = order.clone();
+		relisted.relist_count += 1;
+		Self::create_order(relisted, Zero::zero(), bundle)?;
+		Ok(())
+	}
+
+	// 结算成交价: 第一价格为成交者的出价, 第二价格为次高出价(没有次高出价时为起拍价格)
+	fn clearing_price(order: &OrderOf<T>, winner: &BidOf<T>, runner_up: Option<&BidOf<T>>) -> BalanceOf<T> {
+		match order.settlement_mode {
+			SettlementMode::FirstPrice => winner.price,
+			SettlementMode::SecondPrice => runner_up.map(|bid| bid.price)
+				.unwrap_or(order.start_price)
+				.min(winner.price),
+		}
+	}
+
+	// 成交时处理质押: 开启HonorFullVoteLock且提前成交时, 质押继续锁定到订单原定的到期区块, 否则立即解锁
+	fn release_or_lock_votes(order: &OrderOf<T>, votes: &[VoteOf<T>]) {
+		let now = frame_system::Module::<T>::block_number();
+		if !T::HonorFullVoteLock::get() || now >= order.expire_block {
+			return Self::refund_votes(order.order_id, votes);
+		}
+		for vote in votes {
+			LockedVotes::<T>::insert(order.order_id, &vote.owner, (vote.amount, order.expire_block));
+			Self::deposit_event(RawEvent::VoteLocked(vote.owner.clone(), order.order_id, vote.amount, order.expire_block));
+		}
+	}
+
+	// 关闭合集时撤销订单: 退还竞价(包括冷静期内的一口价购买)、质押和挂单押金, 不收取撤单手续费和补偿
+	fn unwind_order(order: &OrderOf<T>) {
+		let order_id = order.order_id;
+		if let Some((buyer, price, _)) = PendingBuyouts::<T>::take(order_id) {
+			Self::release_bid(order_id, &buyer, price);
+		}
+		Self::clean_order_bid(order_id);
+		Self::remove_order(order);
+		let votes: Vec<VoteOf<T>> = Votes::<T>::take(order_id);
+		Self::refund_votes(order_id, &votes);
+		Self::unreserve_funds(&order.owner, order.deposit);
+		Self::deposit_event(RawEvent::OrderCancel(order.owner.clone(), order_id));
+	}
+
+	// 结算时解锁所有质押, 并为每个质押者发出退还事件
+	fn refund_votes(order_id: T::OrderId, votes: &[VoteOf<T>]) {
+		for vote in votes {
+			Self::unreserve_funds(&vote.owner, vote.amount);
+			Self::deposit_event(RawEvent::VoteRefunded(vote.owner.clone(), order_id, vote.amount));
+		}
+	}
+
+	// 自动结算失败时退还竞价并标记订单, 订单保留在Orders中等待手动结算(按流拍处理)
+	// 卖家违约时挂单押金罚没给国库账户, 竞价者锁定资金不足等其他原因导致的失败退还押金
+	fn fail_order(order: &OrderOf<T>, seller_default: bool) {
+		Self::clean_order_bid(order.order_id);
+		if !order.deposit.is_zero() {
+			if seller_default {
+				Self::slash_to_treasury(&order.owner, order.deposit);
+			} else {
+				Self::unreserve_funds(&order.owner, order.deposit);
+			}
+			let mut order = order.clone();
+			order.deposit = Zero::zero();
+			Orders::<T>::insert(order.order_id, order);
+		}
+		FailedOrders::<T>::insert(order.order_id, ());
+		Self::deposit_event(RawEvent::OrderFailed(order.order_id));
+	}
+
+	// 结算失败是否由卖家违约造成: 挂单的nft已不存在, 卖家无法交付
+	fn is_seller_default(e: &DispatchError) -> bool {
+		*e == DispatchError::from(Error::<T>::NftIdNotExist)
+	}
+
+	// 订单流拍时, 由平台账户按质押权重(质押数量 * 质押区块长度 / 拍卖区块长度)给质押者发放补偿
+	// 平台账户余额不足时跳过
+	fn pay_voter_consolation(order: &OrderOf<T>, votes: &[VoteOf<T>]) {
+		let rate = T::VoterConsolation::get();
+		if rate.is_zero() {
+			return;
+		}
+		let order_keep: u128 = order.keep_block_num.saturated_into();
+		if order_keep == 0 {
+			return;
+		}
+		for vote in votes {
+			let amount: u128 = vote.amount.saturated_into();
+			let keep: u128 = vote.keep_block_num.saturated_into();
+			let weight: u128 = multiply_by_rational(amount, keep, order_keep).unwrap_or(0);
+			let weight: BalanceOf<T> = weight.saturated_into();
+			let consolation = rate.mul_floor(weight);
+			if consolation.is_zero() {
+				continue;
+			}
+			let _ = T::PaymentCurrency::transfer(&T::FeeAccount::get(), &vote.owner, consolation, ExistenceRequirement::KeepAlive);
+		}
+	}
+
+	// 延长订单的拍卖时间, 同时延长已有质押的质押区块长度, 并加入新的自动结算索引
+	fn do_extend_order(order: &mut OrderOf<T>, additional_blocks: T::BlockNumber, who: &T::AccountId) -> dispatch::DispatchResult {
+		let keep_block_num = order.keep_block_num.checked_add(&additional_blocks).ok_or(Error::<T>::KeepBlockNumTooBig)?;
+		ensure!(keep_block_num <= T::MaxKeepBlockNumber::get(), Error::<T>::KeepBlockNumTooBig);
+		let expire_block = order.expire_block.checked_add(&additional_blocks).ok_or(Error::<T>::BlockNumberOverflow)?;
+		let settle_block = expire_block.checked_add(&One::one()).ok_or(Error::<T>::BlockNumberOverflow)?;
+
+		order.keep_block_num = keep_block_num;
+		order.expire_block = expire_block;
+		Orders::<T>::insert(order.order_id, order.clone());
+		Votes::<T>::mutate(order.order_id, |votes| {
+			for vote in votes.iter_mut() {
+				vote.keep_block_num = vote.keep_block_num.saturating_add(additional_blocks);
+			}
+		});
+		ExpiringOrders::<T>::append(settle_block, order.order_id);
+		Self::deposit_event(RawEvent::OrderExtended(who.clone(), order.order_id, expire_block));
+		Ok(())
+	}
+
+	// 根据order中的expire_block检查是否到期
+	// 到期则返回true，否则返回false
+	fn is_time_to_settlement(order: &OrderOf<T>) -> bool {
+		frame_system::Module::<T>::block_number() > order.expire_block
+	}
+
+	// 订单是否已经到了开始区块
+	fn is_started(order: &OrderOf<T>) -> bool {
+		frame_system::Module::<T>::block_number() >= order.start_block
+	}
+
+
+	// 成交价拆分规则: 平台手续费和结算奖励都按成交价向下取整, 结算奖励不超过扣除手续费后的剩余部分,
+	// 剩余部分(包括舍入误差)归卖家(分红从卖家部分中扣除), 三部分之和始终等于成交价, 不会凭空产生或销毁资金
+	pub fn split_price(price: BalanceOf<T>, fee_rate: Permill, reward_rate: Permill) -> (BalanceOf<T>, BalanceOf<T>, BalanceOf<T>) {
+		let fee = fee_rate.mul_floor(price);
+		let remaining = price.saturating_sub(fee);
+		let reward = reward_rate.mul_floor(price).min(remaining);
+		(fee, reward, remaining.saturating_sub(reward))
+	}
+
+	#[transactional]
+	fn order_complete(
+		order: &OrderOf<T>,
+		bid: &T::AccountId, // 购买者
+		price: BalanceOf<T>, // 最终购买价格
+		settlement: Option<&T::AccountId> // 手动结算人, 自动结算和直接成交时为None
+	) -> dispatch::DispatchResult {
+		// 订单的nft必须仍然存在, 避免转移已销毁的nft使其复活
+		let nft_ids = Self::order_nfts(order);
+		ensure!(nft_ids.iter().all(|nft_id| Nfts::<T>::contains_key(nft_id)), Error::<T>::NftIdNotExist);
+		// 只有手动结算时奖励结算人
+		let reward_rate = if settlement.is_some() { T::SettlementReward::get() } else { Permill::zero() };
+		let fee_rate = order.fee_rate.unwrap_or_else(T::PlatformFeeRate::get);
+		let (fee, reward, proceeds) = Self::split_price(price, fee_rate, reward_rate);
+		// 购买者的资金在竞价时已经锁定, 直接转移锁定的资金, 不影响其可用余额
+		Self::repatriate_bid(order.order_id, bid, &T::FeeAccount::get(), fee)?;
+		// 奖励手动结算人
+		if let Some(settler) = settlement {
+			Self::repatriate_bid(order.order_id, bid, settler, reward)?;
+		}
+		// 先向已有股东分红, 本次成交的质押者不参与本次分红
+		let dividend = Self::distribute_dividend(order, bid, price)?;
+		// 按照分润算法计算质押者的利润
+		let votes: Vec<VoteOf<T>> = Votes::<T>::take(order.order_id);
+		// 质押人数不足MinVotersForPayout时不分配利润, 防止卖家以单个关联账户质押后自买自卖获取全部分润
+		let payouts = if Self::meets_min_voters(&votes) {
+			let shares = Self::compute_shares(&order, price, &Self::effective_votes(order, &votes))?;
+			Self::share_payouts(price, &shares)?
+		} else {
+			Vec::new()
+		};
+		// 质押者的利润从卖家所得中扣除, 剩余部分再按收款比例分给卖家或收款账户
+		let profit_total = payouts.iter().fold(Zero::zero(), |acc: BalanceOf<T>, (_, amount)| acc.saturating_add(*amount));
+		let seller_amount = proceeds.checked_sub(&dividend)
+			.and_then(|amount| amount.checked_sub(&profit_total))
+			.ok_or(Error::<T>::PayoutExceedsProceeds)?;
+		Self::pay_seller(order, bid, seller_amount)?;
+		// 质押者的利润同样从购买者锁定的资金中支付, 并按实际分得的利润获得nft的分红股份
+		for (who, profit_amount) in payouts {
+			Self::repatriate_bid(order.order_id, bid, &who, profit_amount)?;
+			Self::add_nft_shares(order.nft_id, &who, profit_amount);
+		}
+		// 移除订单索引, 退还挂单押金, 解锁质押
+		Self::remove_order(order);
+		Self::unreserve_funds(&order.owner, order.deposit);
+		Self::release_or_lock_votes(order, &votes);
+		Self::transfer_sold_nfts(nft_ids, bid, price);
+		Self::deposit_event(RawEvent::OrderComplete(bid.clone(), order.order_id));
+		Ok(())
+	}
+
+	// 更新nft账户索引和成交统计, 打包出售时转移包中所有nft, 每个nft都按整个包的成交价统计
+	fn transfer_sold_nfts(nft_ids: Vec<T::NftId>, bid: &T::AccountId, price: BalanceOf<T>) {
+		for nft_id in nft_ids {
+			Self::change_nft_owner(nft_id, bid);
+			NftSalesStats::<T>::mutate(nft_id, |(count, volume, last_price)| {
+				*count = count.saturating_add(1);
+				*volume = volume.saturating_add(price);
+				*last_price = price;
+			});
+			LastSoldBlock::<T>::insert(nft_id, frame_system::Module::<T>::block_number());
+		}
+	}
+
+	// 自动结算失败时的兜底成交: 不再分红和分润, 只将最高出价者锁定的资金扣除手续费后直接转给卖家, 并转移nft
+	// 兜底成交也失败时回滚所有修改并返回错误
+	#[transactional]
+	fn fallback_settle(order: &OrderOf<T>, bid: &BidOf<T>) -> dispatch::DispatchResult {
+		let order_id = order.order_id;
+		let nft_ids = Self::order_nfts(order);
+		ensure!(nft_ids.iter().all(|nft_id| Nfts::<T>::contains_key(nft_id)), Error::<T>::NftIdNotExist);
+		let queue = BidQueue::<T>::take(order_id);
+		let price = Self::clearing_price(order, bid, queue.first());
+		let fee_rate = order.fee_rate.unwrap_or_else(T::PlatformFeeRate::get);
+		let (fee, _, proceeds) = Self::split_price(price, fee_rate, Permill::zero());
+		Self::repatriate_bid(order_id, &bid.owner, &T::FeeAccount::get(), fee)?;
+		Self::repatriate_bid(order_id, &bid.owner, &order.owner, proceeds)?;
+		Bids::<T>::remove(order_id);
+		// 退还出价与成交价的差额和所有候补出价
+		Self::release_bid(order_id, &bid.owner, bid.price.saturating_sub(price));
+		for queued in queue {
+			Self::release_bid(order_id, &queued.owner, queued.price);
+		}
+		Self::remove_order(order);
+		Self::unreserve_funds(&order.owner, order.deposit);
+		let votes: Vec<VoteOf<T>> = Votes::<T>::take(order_id);
+		Self::release_or_lock_votes(order, &votes);
+		Self::transfer_sold_nfts(nft_ids, &bid.owner, price);
+		Self::deposit_event(RawEvent::OrderComplete(bid.owner.clone(), order_id));
+		Ok(())
+	}
+
+	// 将卖家所得按收款比例转给各收款账户, 舍入误差归最后一个收款账户, 没有设置收款账户时全部转给卖家
+	fn pay_seller(order: &OrderOf<T>, bid: &T::AccountId, amount: BalanceOf<T>) -> dispatch::DispatchResult {
+		let (last, rest) = match order.payees.split_last() {
+			Some(split) => split,
+			None => return Self::repatriate_bid(order.order_id, bid, &order.owner, amount),
+		};
+		let mut remaining = amount;
+		for (payee, rate) in rest {
+			let share = rate.mul_floor(amount).min(remaining);
+			Self::repatriate_bid(order.order_id, bid, payee, share)?;
+			remaining = remaining.saturating_sub(share);
+		}
+		Self::repatriate_bid(order.order_id, bid, &last.0, remaining)
+	}
+
+	// 质押人数是否达到分配利润所需的最少人数, 同一账户的多次质押已合并为一条记录
+	fn meets_min_voters(votes: &[VoteOf<T>]) -> bool {
+		votes.len() as u32 >= T::MinVotersForPayout::get()
+	}
+
+	// 提前成交时质押实际锁定的区块数少于声明的质押区块长度, 按min(声明长度, 实际经过区块数)计算权重
+	// 质押时的区块号 = 到期区块 - 声明长度, 实际经过区块数 = 当前区块 - 质押时的区块号
+	fn effective_votes(order: &OrderOf<T>, votes: &[VoteOf<T>]) -> Vec<VoteOf<T>> {
+		let now = frame_system::Module::<T>::block_number();
+		let unspent = order.expire_block.saturating_sub(now);
+		votes.iter().map(|vote| {
+			let mut vote = vote.clone();
+			vote.keep_block_num = vote.keep_block_num.saturating_sub(unspent);
+			vote
+		}).collect()
+	}
+
+	// 以当前区块为创建区块插入订单及其索引, 分配新的订单Id
+	// bundle为打包出售的所有nft, 单个nft出售时为空
+	fn create_order(mut order: OrderOf<T>, start_delay: T::BlockNumber, bundle: Vec<T::NftId>) -> Result<T::OrderId, DispatchError> {
+		// 计算订单开始区块和到期区块, 到期区块的下一个区块可以结算
+		let create_block = frame_system::Module::<T>::block_number();
+		let start_block = create_block.checked_add(&start_delay).ok_or(Error::<T>::BlockNumberOverflow)?;
+		let expire_block = start_block.checked_add(&order.keep_block_num).ok_or(Error::<T>::BlockNumberOverflow)?;
+		let settle_block = expire_block.checked_add(&One::one()).ok_or(Error::<T>::BlockNumberOverflow)?;
+
+		NextOrderId::<T>::try_mutate(|id| -> Result<T::OrderId, DispatchError> {
+			let order_id = *id;
+			*id = id.checked_add(&One::one()).ok_or(Error::<T>::OrderIdOverflow)?;
+			Self::check_id_exhaustion(*id, IdCounter::Order);
+			order.order_id = order_id;
+			order.create_block = create_block;
+			order.start_block = start_block;
+			order.expire_block = expire_block;
+			// 插入订单索引
+			Orders::<T>::insert(order_id, order.clone());
+			NftOrder::<T>::insert(order.nft_id, order_id);
+			for nft_id in bundle.iter() {
+				NftOrder::<T>::insert(nft_id, order_id);
+			}
+			if !bundle.is_empty() {
+				BundleOrders::<T>::insert(order_id, bundle);
+			}
+			ActiveOrders::<T>::insert(order_id, ());
+			OrderCountByOwner::<T>::mutate(&order.owner, |count| *count += 1);
+			let votes: Vec<VoteOf<T>> = Vec::new();
+			Votes::<T>::insert(order_id, votes);
+			ExpiringOrders::<T>::append(settle_block, order_id);
+			Self::deposit_event(RawEvent::OrderSell(order.owner, order_id, expire_block));
+			Ok(order_id)
+		})
+	}
+
+	// 分配Id后剩余可用的Id数低于阈值时发出预警事件
+	fn check_id_exhaustion<I: AtLeast32BitUnsigned + Bounded>(next: I, counter: IdCounter) {
+		let remaining: u128 = I::max_value().saturating_sub(next).saturated_into();
+		if remaining < T::IdExhaustionThreshold::get() as u128 {
+			Self::deposit_event(RawEvent::IdCounterNearExhaustion(counter, remaining));
+		}
+	}
+
+	// 订单出售的所有nft, 打包出售时为整个包, 否则为订单的nft
+	pub fn order_nfts(order: &OrderOf<T>) -> Vec<T::NftId> {
+		let bundle = BundleOrders::<T>::get(order.order_id);
+		if bundle.is_empty() { vec![order.nft_id] } else { bundle }
+	}
+
+	// 移除订单及其索引
+	fn remove_order(order: &OrderOf<T>) {
+		Orders::<T>::remove(order.order_id);
+		for nft_id in Self::order_nfts(order) {
+			NftOrder::<T>::remove(nft_id);
+		}
+		BundleOrders::<T>::remove(order.order_id);
+		ActiveOrders::<T>::remove(order.order_id);
+		VoterCount::<T>::remove(order.order_id);
+		OrderVoteTotal::<T>::remove(order.order_id);
+		BidHistory::<T>::remove(order.order_id);
+		BidQueue::<T>::remove(order.order_id);
+		FailedOrders::<T>::remove(order.order_id);
+		OrderCountByOwner::<T>::mutate_exists(&order.owner, |count| {
+			*count = count.map(|c| c.saturating_sub(1)).filter(|c| *c > 0);
+		});
+	}
+
+	// nft已有股东时, 从成交价中提取DividendRate比例的资金按股份分给股东, 返回实际分配的总额
+	fn distribute_dividend(order: &OrderOf<T>, bid: &T::AccountId, price: BalanceOf<T>) -> Result<BalanceOf<T>, DispatchError> {
+		let nft_id = order.nft_id;
+		let total_shares: u128 = NftTotalShares::<T>::get(nft_id).saturated_into();
+		if total_shares == 0 {
+			return Ok(Zero::zero());
+		}
+		let dividend: u128 = T::DividendRate::get().mul_floor(price).saturated_into();
+		let mut credited: BalanceOf<T> = Zero::zero();
+		for (who, shares) in NftShares::<T>::iter_prefix(nft_id) {
+			let amount = multiply_by_rational(dividend, shares.saturated_into(), total_shares).unwrap_or(0);
+			let amount: BalanceOf<T> = amount.saturated_into();
+			if amount.is_zero() {
+				continue;
+			}
+			Dividends::<T>::mutate(nft_id, &who, |d| *d = d.saturating_add(amount));
+			credited = credited.saturating_add(amount);
+		}
+		Self::repatriate_bid(order.order_id, bid, &T::DividendAccount::get(), credited)?;
+		DividendPool::<T>::mutate(nft_id, |pool| *pool = pool.saturating_add(credited));
+		Ok(credited)
+	}
+
+	// 增加账户持有的nft分红股份
+	fn add_nft_shares(nft_id: T::NftId, who: &T::AccountId, shares: BalanceOf<T>) {
+		if shares.is_zero() {
+			return;
+		}
+		NftShares::<T>::mutate(nft_id, who, |s| *s = s.saturating_add(shares));
+		NftTotalShares::<T>::mutate(nft_id, |s| *s = s.saturating_add(shares));
+	}
+
+	// 合并质押时按质押数量加权平均质押区块长度, 使合并后的权重(amount * keep_block_num)与分开质押时相同
+	fn merge_keep_block_num(vote: &VoteOf<T>, amount: BalanceOf<T>, keep_block_num: T::BlockNumber) -> T::BlockNumber {
+		let old_amount: u128 = vote.amount.saturated_into();
+		let old_keep: u128 = vote.keep_block_num.saturated_into();
+		let amount: u128 = amount.saturated_into();
+		let keep: u128 = keep_block_num.saturated_into();
+		let total = old_amount.saturating_add(amount);
+		if total == 0 {
+			return keep_block_num;
+		}
+		let merged = old_amount.saturating_mul(old_keep)
+			.saturating_add(amount.saturating_mul(keep)) / total;
+		merged.saturated_into()
+	}
+
+	// 分润算法: 计算每个质押者获得的凭证数量, 不修改任何状态
+	// inputs中每个账户只有一条质押记录(vote_order会合并重复质押)
+	// 质押先按质押区块长度降序、再按账户升序排序后计算, 结果与质押到达的顺序无关, 返回值按排序后的顺序排列
+	// 所有定点数运算都做溢出检查: 初始股权数溢出时截断为MaxShareStock, 其余溢出返回ShareOverflow
+	fn compute_shares(
+		order: &OrderOf<T>, // 最大拍卖区块数
+		bid_price: BalanceOf<T>, // 购买价格
+		inputs: &[VoteOf<T>] //质押列表
+	) -> Result<Vec<(T::AccountId, U64F64)>, DispatchError> {
+		if inputs.is_empty() {
+			return Ok(vec![])
+		}
+		let mut inputs: Vec<&VoteOf<T>> = inputs.iter().collect();
+		inputs.sort_by(|a, b| b.keep_block_num.cmp(&a.keep_block_num).then_with(|| a.owner.cmp(&b.owner)));
+		let overflow = || DispatchError::from(Error::<T>::ShareOverflow);
+		let fix_rate: U64F64 = U64F64::from_num(T::FixRate::get());
+		let profit_rate: U64F64 = U64F64::from_num(T::ProfitRate::get());
+		let day_block_num: u128 = T::DayBlockNum::get().saturated_into();
+		let day_block_num: U64F64 = U64F64::checked_from_num(day_block_num).ok_or_else(overflow)?;
+		let block_num: u128 = order.keep_block_num.saturated_into();
+		let block_num: U64F64 = U64F64::checked_from_num(block_num).ok_or_else(overflow)?;
+		let bid_price: u128 = bid_price.saturated_into();
+		let bid_price: U64F64 = U64F64::checked_from_num(bid_price).ok_or_else(overflow)?;
+
+		let day: U64F64 = block_num.checked_div(day_block_num).ok_or_else(overflow)?;
+		let max_stock: U64F64 = U64F64::from_num(T::MaxShareStock::get());
+		// 初始股权数
+		let stock: U64F64 = match bid_price.checked_mul(profit_rate)
+			.and_then(|v| v.checked_div(day))
+			.and_then(|v| v.checked_mul(U64F64::from_num(T::DaysPerYear::get())))
+		{
+			Some(stock) if stock <= max_stock => stock,
+			_ => {
+				debug::warn!("初始股权数超出上限, 截断为: {}", max_stock);
+				max_stock
+			}
+		};
+
+		debug::warn!(
+			"=>当前价格为: {}, 分成比例为: {}%, 拍卖时长: {}day, 初始股权数: {}, 固定年化: {}%",
+			bid_price,
+			profit_rate,
+			day,
+			stock,
+			fix_rate
+		);
+
+		let mut is_fixed: bool = false; // 是否开启固定利率
+		let mut total: U64F64 = U64F64::from_num(0.0); // 总质押数量
 		let mut weight_rate: U64F64 = U64F64::from_num(0.0); // 汇率
 		let mut tt: U64F64 = U64F64::from_num(0.0);
-		let mut vote_res: Vec<(VoteOf<T>, U64F64)> = vec![];
+		let mut vote_res: Vec<(T::AccountId, U64F64)> = vec![];
 		for vote in inputs {
 			let amount: u128 = vote.amount.saturated_into();
-			let amount: U64F64 = U64F64::from_num(amount);
+			let amount: U64F64 = U64F64::checked_from_num(amount).ok_or_else(overflow)?;
 			let keep_block_num: u128 = vote.keep_block_num.saturated_into();
-			let keep_block_num: U64F64 = U64F64::from_num(keep_block_num);
-			let vote_day: U64F64 = keep_block_num / day_block_num;
+			let keep_block_num: U64F64 = U64F64::checked_from_num(keep_block_num).ok_or_else(overflow)?;
+			let vote_day: U64F64 = keep_block_num.checked_div(day_block_num).ok_or_else(overflow)?;
 
-			let pre_weight: U64F64 = amount * vote_day / day; // 质押权重
-			total += pre_weight;
+			// 质押权重
+			let pre_weight: U64F64 = Self::vote_pre_weight(amount, vote_day, day).ok_or_else(overflow)?;
+			// 权重为0的质押(例如在到期区块质押)不获得凭证
+			if pre_weight == U64F64::from_num(0) {
+				vote_res.push((vote.owner.clone(), pre_weight));
+				continue;
+			}
 
+			// 汇率只由之前的质押总权重决定, 不包含本次质押自身的权重, 第一个质押者的汇率为1
 			if !is_fixed {
-				weight_rate = stock / (stock + total); // 随着质押数量的增加,逐渐变小
+				// 随着质押数量的增加,逐渐变小
+				weight_rate = stock.checked_add(total)
+					.and_then(|v| stock.checked_div(v))
+					.ok_or_else(overflow)?;
 			}
-			let t: U64F64 = pre_weight * weight_rate;
-			tt += t;
-			let year_rate: U64F64 = t / tt * stock / pre_weight; // 年化收益率
+			total = total.checked_add(pre_weight).ok_or_else(overflow)?;
+			let t: U64F64 = pre_weight.checked_mul(weight_rate).ok_or_else(overflow)?;
+			tt = tt.checked_add(t).ok_or_else(overflow)?;
+			// 年化收益率
+			let year_rate: U64F64 = t.checked_div(tt)
+				.and_then(|v| v.checked_mul(stock))
+				.and_then(|v| v.checked_div(pre_weight))
+				.ok_or_else(overflow)?;
 			if year_rate < fix_rate {
 				is_fixed = true;
 			}
-			vote_res.push((vote, t));
+			vote_res.push((vote.owner.clone(), t));
 
 			debug::warn!(
 				"质押数量: {}, 质押时长: {}day, 当前汇率: {}, 当前年收益率为: {}, 此次获得的凭证为: {}/{}",
@@ -480,17 +2758,210 @@ impl<T: Trait> Module<T> {
 				tt
 			)
 		}
-		let profit_amount: U64F64 = profit_rate * bid_price;
-		for (vote, t) in vote_res {
-			T::Currency::unreserve(&vote.owner, vote.amount);
-			let profit_amount: U64F64 = profit_amount / tt * t;
+		Ok(vote_res)
+	}
+
+	// 质押权重 = 质押数量 * 质押天数 / 拍卖天数
+	fn vote_pre_weight(amount: U64F64, vote_day: U64F64, day: U64F64) -> Option<U64F64> {
+		amount.checked_mul(vote_day).and_then(|v| v.checked_div(day))
+	}
+
+	// 查询在订单中质押amount数量、keep_block_num个区块时的质押权重(向下取整), 订单不存在或溢出时返回0
+	pub fn vote_weight(order_id: T::OrderId, amount: BalanceOf<T>, keep_block_num: T::BlockNumber) -> u128 {
+		Orders::<T>::get(order_id)
+			.and_then(|order| Self::try_vote_weight(&order, amount, keep_block_num))
+			.map(|weight| weight.to_num::<u128>())
+			.unwrap_or(0)
+	}
+
+	fn try_vote_weight(order: &OrderOf<T>, amount: BalanceOf<T>, keep_block_num: T::BlockNumber) -> Option<U64F64> {
+		let day_block_num: u128 = T::DayBlockNum::get().saturated_into();
+		let day_block_num: U64F64 = U64F64::checked_from_num(day_block_num)?;
+		let block_num: u128 = order.keep_block_num.saturated_into();
+		let day: U64F64 = U64F64::checked_from_num(block_num)?.checked_div(day_block_num)?;
+		let amount: u128 = amount.saturated_into();
+		let keep_block_num: u128 = keep_block_num.saturated_into();
+		let vote_day: U64F64 = U64F64::checked_from_num(keep_block_num)?.checked_div(day_block_num)?;
+		Self::vote_pre_weight(U64F64::checked_from_num(amount)?, vote_day, day)
+	}
+
+	// 根据凭证数量计算每个质押者分得的利润, 向下取整
+	// 与compute_shares相同, 所有定点数运算都做溢出检查, 溢出时返回ShareOverflow
+	fn share_payouts(bid_price: BalanceOf<T>, shares: &[(T::AccountId, U64F64)]) -> Result<Vec<(T::AccountId, BalanceOf<T>)>, DispatchError> {
+		let overflow = || DispatchError::from(Error::<T>::ShareOverflow);
+		let profit_rate: U64F64 = U64F64::from_num(T::ProfitRate::get());
+		let bid_price: u128 = bid_price.saturated_into();
+		let bid_price: U64F64 = U64F64::checked_from_num(bid_price).ok_or_else(overflow)?;
+		let profit_amount: U64F64 = profit_rate.checked_mul(bid_price).ok_or_else(overflow)?;
+		let tt: U64F64 = shares.iter()
+			.try_fold(U64F64::from_num(0), |acc, (_, t)| acc.checked_add(*t))
+			.ok_or_else(overflow)?;
+		if tt == U64F64::from_num(0) {
+			return Ok(vec![])
+		}
+		shares.iter().map(|(who, t)| {
+			let profit_amount: U64F64 = profit_amount.checked_div(tt)
+				.and_then(|v| v.checked_mul(*t))
+				.ok_or_else(overflow)?;
 			let profit_amount: u128 = profit_amount.floor().to_num();
-			let profit_amount: BalanceOf<T> = profit_amount.saturated_into();
-			let _ = T::Currency::transfer(&order.owner, &vote.owner, profit_amount,
-								  ExistenceRequirement::KeepAlive
-			);
+			Ok((who.clone(), profit_amount.saturated_into()))
+		}).collect()
+	}
+
+	// 预估在当前质押列表中加入一笔质押后, 每个质押者按当前最高出价(没有出价时按起拍价)可以分得的利润
+	pub fn simulate_shares(
+		order_id: T::OrderId,
+		who: T::AccountId,
+		amount: BalanceOf<T>,
+		keep_block_num: T::BlockNumber
+	) -> Vec<(T::AccountId, u128)> {
+		let order: OrderOf<T> = match Orders::<T>::get(order_id) {
+			Some(order) => order,
+			None => return vec![],
+		};
+		let mut votes: Vec<VoteOf<T>> = Votes::<T>::get(order_id);
+		if let Some(vote) = votes.iter_mut().find(|v| v.owner == who) {
+			vote.keep_block_num = Self::merge_keep_block_num(vote, amount, keep_block_num);
+			vote.amount = vote.amount.saturating_add(amount);
+		} else {
+			votes.push(Vote {
+				order_id,
+				amount,
+				keep_block_num,
+				owner: who,
+			});
+		}
+		if !Self::meets_min_voters(&votes) {
+			return vec![];
+		}
+		let bid_price = Bids::<T>::get(order_id).map(|bid| bid.price).unwrap_or(order.start_price);
+		let shares = match Self::compute_shares(&order, bid_price, &votes) {
+			Ok(shares) => shares,
+			Err(_) => return vec![],
+		};
+		match Self::share_payouts(bid_price, &shares) {
+			Ok(payouts) => payouts.into_iter()
+				.map(|(who, amount)| (who, amount.saturated_into()))
+				.collect(),
+			Err(_) => vec![],
+		}
+	}
+
+	// 查询订单状态, 订单不存在时返回None
+	pub fn order_status(order_id: T::OrderId) -> Option<OrderStatus> {
+		let order: OrderOf<T> = Orders::<T>::get(order_id)?;
+		if !Self::is_time_to_settlement(&order) {
+			Some(OrderStatus::Live)
+		} else if Bids::<T>::contains_key(order_id) {
+			Some(OrderStatus::EndedWithBid)
+		} else {
+			Some(OrderStatus::EndedNoBid)
+		}
+	}
+
+	// 查询当前可以结算的订单Id
+	// 需要遍历全部订单, 复杂度为O(n), 只用于链下查询; 链上按区块结算请使用ExpiringOrders索引
+	pub fn settleable_orders() -> Vec<T::OrderId> {
+		Orders::<T>::iter()
+			.filter(|(_, order)| Self::is_time_to_settlement(order))
+			.map(|(order_id, _)| order_id)
+			.collect()
+	}
+
+	// 查询所有订单中锁定的资金总额(出价、质押和挂单押金, 包括转入托管账户的出价)
+	pub fn total_value_locked() -> BalanceOf<T> {
+		TotalReserved::<T>::get()
+	}
+
+	// 查询订单的出价历史, 按出价顺序排列
+	pub fn bid_history(order_id: T::OrderId) -> Vec<(T::AccountId, BalanceOf<T>, T::BlockNumber)> {
+		BidHistory::<T>::get(order_id)
+	}
+
+	// 查询订单距离可以结算还剩多少区块, 订单不存在时返回None, 已经可以结算时返回0
+	pub fn blocks_until_settlement(order_id: T::OrderId) -> Option<T::BlockNumber> {
+		let order = Orders::<T>::get(order_id)?;
+		let now = frame_system::Module::<T>::block_number();
+		let settle_block = order.expire_block.checked_add(&One::one())?;
+		Some(settle_block.checked_sub(&now).unwrap_or_else(Zero::zero))
+	}
+
+	// 查询订单中不同质押账户的数量
+	pub fn voter_count(order_id: T::OrderId) -> u32 {
+		VoterCount::<T>::get(order_id)
+	}
+
+	// 查询nft的所有权历史, 按取得所有权的顺序排列
+	pub fn provenance(nft_id: T::NftId) -> Vec<(T::AccountId, T::BlockNumber)> {
+		OwnershipHistory::<T>::get(nft_id)
+	}
+
+	// 查询nft的成交统计: (成交次数, 累计成交额, 最近成交价)
+	pub fn sales_stats(nft_id: T::NftId) -> (u32, BalanceOf<T>, BalanceOf<T>) {
+		NftSalesStats::<T>::get(nft_id)
+	}
+
+	// 查询所有正在拍卖中的订单Id
+	pub fn active_orders() -> Vec<T::OrderId> {
+		ActiveOrders::<T>::iter().map(|(order_id, _)| order_id).collect()
+	}
+
+	// 查询订单详情, 包括当前最高出价、质押总额和质押人数
+	pub fn order_detail(order_id: T::OrderId) -> Option<OrderDetailOf<T>> {
+		let order = Orders::<T>::get(order_id)?;
+		let votes = Votes::<T>::get(order_id);
+		let total_votes = votes.iter().fold(Zero::zero(), |acc: BalanceOf<T>, vote| acc.saturating_add(vote.amount));
+		Some(OrderDetail {
+			order,
+			bid: Bids::<T>::get(order_id),
+			total_votes,
+			voter_count: VoterCount::<T>::get(order_id),
+		})
+	}
+
+	// 分页查询订单, 从start_key(包含)开始最多返回limit个订单, 并返回下一页的起始订单Id
+	// 订单按存储键顺序遍历, 即使start_key对应的订单已被移除也能从正确位置继续
+	pub fn orders_paged(start_key: Option<T::OrderId>, limit: u32) -> (Vec<(T::OrderId, OrderOf<T>)>, Option<T::OrderId>) {
+		let start_raw_key = start_key.map(|id| Orders::<T>::hashed_key_for(id));
+		let (ids, next_key) = Self::paged_map_keys::<T::OrderId>(&Orders::<T>::final_prefix(), start_raw_key, limit);
+		let page = ids.into_iter()
+			.filter_map(|id| Orders::<T>::get(id).map(|order| (id, order)))
+			.collect();
+		(page, next_key)
+	}
+
+	// 从start_raw_key(包含)开始按存储键顺序读取twox_64_concat映射的键, 最多返回limit个, 并返回下一页的起始键
+	// 直接定位到start_raw_key, 不需要解码它之前的条目, 每页的开销只与limit有关
+	fn paged_map_keys<K: Decode>(prefix: &[u8], start_raw_key: Option<Vec<u8>>, limit: u32) -> (Vec<K>, Option<K>) {
+		let mut current = match start_raw_key {
+			Some(raw_key) if sp_io::storage::exists(&raw_key) => Some(raw_key),
+			Some(raw_key) => sp_io::storage::next_key(&raw_key),
+			None => sp_io::storage::next_key(prefix),
+		}.filter(|raw_key| raw_key.starts_with(prefix));
+		let mut keys = Vec::new();
+		while let Some(raw_key) = current {
+			// 存储键 = 前缀 + 8字节twox64哈希 + 编码后的键
+			let key = raw_key.get(prefix.len() + 8..).and_then(|mut encoded| K::decode(&mut encoded).ok());
+			if let Some(key) = key {
+				if keys.len() >= limit as usize {
+					return (keys, Some(key));
+				}
+				keys.push(key);
+			}
+			current = sp_io::storage::next_key(&raw_key).filter(|next| next.starts_with(prefix));
 		}
+		(keys, None)
 	}
 
+	// 查询所有nft的Id, 需要遍历整个Nfts存储, nft数量较多时开销很大, 应优先使用nft_ids_paged分页查询
+	pub fn all_nft_ids() -> Vec<T::NftId> {
+		Nfts::<T>::iter().map(|(nft_id, _)| nft_id).collect()
+	}
 
-}
\ No newline at end of file
+	// 分页查询nft的Id, 从start_key(包含)开始最多返回limit个, 并返回下一页的起始nftId
+	// 与orders_paged相同, 按存储键顺序遍历
+	pub fn nft_ids_paged(start_key: Option<T::NftId>, limit: u32) -> (Vec<T::NftId>, Option<T::NftId>) {
+		let start_raw_key = start_key.map(|id| Nfts::<T>::hashed_key_for(id));
+		Self::paged_map_keys::<T::NftId>(&Nfts::<T>::final_prefix(), start_raw_key, limit)
+	}
+}
@@ -1,10 +1,11 @@
 use crate::{Module, Trait};
 use sp_core::H256;
-use frame_support::{impl_outer_origin, impl_outer_event ,parameter_types, weights::Weight, traits::OnFinalize, traits::OnInitialize};
+use frame_support::{impl_outer_origin, impl_outer_event ,parameter_types, weights::Weight, traits::{Get, OnFinalize, OnInitialize, StorageMapShim}};
 use sp_runtime::{
-	traits::{BlakeTwo256, IdentityLookup}, testing::Header, Perbill,
+	traits::{BlakeTwo256, IdentityLookup}, testing::Header, Perbill, Permill, ModuleId,
 };
 use frame_system as system;
+use std::cell::RefCell;
 
 impl_outer_origin! {
 	pub enum Origin for Test {}
@@ -18,10 +19,255 @@ impl_outer_event! {
 		system<T>,
 		nft_event<T>,
 		pallet_balances<T>,
+		pallet_balances Instance1<T>,
 	}
 }
 // Configure a mock runtime to test the pallet.
 
+thread_local! {
+	static VOTER_CONSOLATION: RefCell<Permill> = RefCell::new(Permill::zero());
+	static MINT_LOCK_PERIOD: RefCell<u64> = RefCell::new(0);
+	static MAX_QUEUED_BIDS: RefCell<u32> = RefCell::new(0);
+	static LISTING_DEPOSIT: RefCell<u64> = RefCell::new(0);
+	static PRICE_TICK: RefCell<u64> = RefCell::new(1);
+	static UNIQUE_URLS: RefCell<bool> = RefCell::new(false);
+	static BUYOUT_COOLDOWN: RefCell<u64> = RefCell::new(0);
+	static MIN_KEEP_BLOCK_NUMBER: RefCell<u64> = RefCell::new(1);
+	static MAX_RESERVED_PER_ACCOUNT: RefCell<u64> = RefCell::new(u64::max_value());
+	static MAX_ORDERS_PER_BLOCK: RefCell<u32> = RefCell::new(u32::max_value());
+	static RELIST_COOLDOWN: RefCell<u64> = RefCell::new(0);
+	static ALLOW_SELLER_VOTE: RefCell<bool> = RefCell::new(true);
+	static USE_ESCROW: RefCell<bool> = RefCell::new(false);
+	static MIN_VOTERS_FOR_PAYOUT: RefCell<u32> = RefCell::new(0);
+	static MAX_LISTINGS_PER_NFT: RefCell<u32> = RefCell::new(u32::max_value());
+	static DAYS_PER_YEAR: RefCell<u32> = RefCell::new(365);
+	static MAX_TOTAL_VOTES_PER_ORDER: RefCell<u64> = RefCell::new(u64::max_value());
+	static HONOR_FULL_VOTE_LOCK: RefCell<bool> = RefCell::new(false);
+	static CANCELLATION_FEE: RefCell<u64> = RefCell::new(0);
+	static PAYMENT_EXISTENTIAL_DEPOSIT: RefCell<u64> = RefCell::new(1);
+}
+
+pub struct VoterConsolation;
+impl Get<Permill> for VoterConsolation {
+	fn get() -> Permill {
+		VOTER_CONSOLATION.with(|v| *v.borrow())
+	}
+}
+
+pub fn set_voter_consolation(rate: Permill) {
+	VOTER_CONSOLATION.with(|v| *v.borrow_mut() = rate);
+}
+
+pub struct MintLockPeriod;
+impl Get<u64> for MintLockPeriod {
+	fn get() -> u64 {
+		MINT_LOCK_PERIOD.with(|v| *v.borrow())
+	}
+}
+
+pub fn set_mint_lock_period(period: u64) {
+	MINT_LOCK_PERIOD.with(|v| *v.borrow_mut() = period);
+}
+
+pub struct MaxQueuedBids;
+impl Get<u32> for MaxQueuedBids {
+	fn get() -> u32 {
+		MAX_QUEUED_BIDS.with(|v| *v.borrow())
+	}
+}
+
+pub fn set_max_queued_bids(max: u32) {
+	MAX_QUEUED_BIDS.with(|v| *v.borrow_mut() = max);
+}
+
+pub struct ListingDeposit;
+impl Get<u64> for ListingDeposit {
+	fn get() -> u64 {
+		LISTING_DEPOSIT.with(|v| *v.borrow())
+	}
+}
+
+pub fn set_listing_deposit(deposit: u64) {
+	LISTING_DEPOSIT.with(|v| *v.borrow_mut() = deposit);
+}
+
+pub struct PriceTick;
+impl Get<u64> for PriceTick {
+	fn get() -> u64 {
+		PRICE_TICK.with(|v| *v.borrow())
+	}
+}
+
+pub fn set_price_tick(tick: u64) {
+	PRICE_TICK.with(|v| *v.borrow_mut() = tick);
+}
+
+pub struct UniqueUrls;
+impl Get<bool> for UniqueUrls {
+	fn get() -> bool {
+		UNIQUE_URLS.with(|v| *v.borrow())
+	}
+}
+
+pub fn set_unique_urls(unique: bool) {
+	UNIQUE_URLS.with(|v| *v.borrow_mut() = unique);
+}
+
+pub struct BuyoutCooldown;
+impl Get<u64> for BuyoutCooldown {
+	fn get() -> u64 {
+		BUYOUT_COOLDOWN.with(|v| *v.borrow())
+	}
+}
+
+pub fn set_buyout_cooldown(blocks: u64) {
+	BUYOUT_COOLDOWN.with(|v| *v.borrow_mut() = blocks);
+}
+
+pub struct MinKeepBlockNumber;
+impl Get<u64> for MinKeepBlockNumber {
+	fn get() -> u64 {
+		MIN_KEEP_BLOCK_NUMBER.with(|v| *v.borrow())
+	}
+}
+
+pub fn set_min_keep_block_number(blocks: u64) {
+	MIN_KEEP_BLOCK_NUMBER.with(|v| *v.borrow_mut() = blocks);
+}
+
+pub struct MaxReservedPerAccount;
+impl Get<u64> for MaxReservedPerAccount {
+	fn get() -> u64 {
+		MAX_RESERVED_PER_ACCOUNT.with(|v| *v.borrow())
+	}
+}
+
+pub fn set_max_reserved_per_account(max: u64) {
+	MAX_RESERVED_PER_ACCOUNT.with(|v| *v.borrow_mut() = max);
+}
+
+pub struct MaxOrdersPerBlock;
+impl Get<u32> for MaxOrdersPerBlock {
+	fn get() -> u32 {
+		MAX_ORDERS_PER_BLOCK.with(|v| *v.borrow())
+	}
+}
+
+pub fn set_max_orders_per_block(max: u32) {
+	MAX_ORDERS_PER_BLOCK.with(|v| *v.borrow_mut() = max);
+}
+
+pub struct RelistCooldown;
+impl Get<u64> for RelistCooldown {
+	fn get() -> u64 {
+		RELIST_COOLDOWN.with(|v| *v.borrow())
+	}
+}
+
+pub fn set_relist_cooldown(blocks: u64) {
+	RELIST_COOLDOWN.with(|v| *v.borrow_mut() = blocks);
+}
+
+pub struct AllowSellerVote;
+impl Get<bool> for AllowSellerVote {
+	fn get() -> bool {
+		ALLOW_SELLER_VOTE.with(|v| *v.borrow())
+	}
+}
+
+pub fn set_allow_seller_vote(allow: bool) {
+	ALLOW_SELLER_VOTE.with(|v| *v.borrow_mut() = allow);
+}
+
+pub struct UseEscrow;
+impl Get<bool> for UseEscrow {
+	fn get() -> bool {
+		USE_ESCROW.with(|v| *v.borrow())
+	}
+}
+
+pub fn set_use_escrow(use_escrow: bool) {
+	USE_ESCROW.with(|v| *v.borrow_mut() = use_escrow);
+}
+
+pub struct MinVotersForPayout;
+impl Get<u32> for MinVotersForPayout {
+	fn get() -> u32 {
+		MIN_VOTERS_FOR_PAYOUT.with(|v| *v.borrow())
+	}
+}
+
+pub fn set_min_voters_for_payout(min_voters: u32) {
+	MIN_VOTERS_FOR_PAYOUT.with(|v| *v.borrow_mut() = min_voters);
+}
+
+pub struct MaxListingsPerNft;
+impl Get<u32> for MaxListingsPerNft {
+	fn get() -> u32 {
+		MAX_LISTINGS_PER_NFT.with(|v| *v.borrow())
+	}
+}
+
+pub fn set_max_listings_per_nft(max: u32) {
+	MAX_LISTINGS_PER_NFT.with(|v| *v.borrow_mut() = max);
+}
+
+pub struct DaysPerYear;
+impl Get<u32> for DaysPerYear {
+	fn get() -> u32 {
+		DAYS_PER_YEAR.with(|v| *v.borrow())
+	}
+}
+
+pub fn set_days_per_year(days: u32) {
+	DAYS_PER_YEAR.with(|v| *v.borrow_mut() = days);
+}
+
+pub struct MaxTotalVotesPerOrder;
+impl Get<u64> for MaxTotalVotesPerOrder {
+	fn get() -> u64 {
+		MAX_TOTAL_VOTES_PER_ORDER.with(|v| *v.borrow())
+	}
+}
+
+pub fn set_max_total_votes_per_order(max: u64) {
+	MAX_TOTAL_VOTES_PER_ORDER.with(|v| *v.borrow_mut() = max);
+}
+
+pub struct HonorFullVoteLock;
+impl Get<bool> for HonorFullVoteLock {
+	fn get() -> bool {
+		HONOR_FULL_VOTE_LOCK.with(|v| *v.borrow())
+	}
+}
+
+pub fn set_honor_full_vote_lock(honor: bool) {
+	HONOR_FULL_VOTE_LOCK.with(|v| *v.borrow_mut() = honor);
+}
+
+pub struct CancellationFee;
+impl Get<u64> for CancellationFee {
+	fn get() -> u64 {
+		CANCELLATION_FEE.with(|v| *v.borrow())
+	}
+}
+
+pub fn set_cancellation_fee(fee: u64) {
+	CANCELLATION_FEE.with(|v| *v.borrow_mut() = fee);
+}
+
+// 支付货币的最低存在余额, 创世之后再修改, 避免创世余额低于该值
+pub struct PaymentExistentialDeposit;
+impl Get<u64> for PaymentExistentialDeposit {
+	fn get() -> u64 {
+		PAYMENT_EXISTENTIAL_DEPOSIT.with(|v| *v.borrow())
+	}
+}
+
+pub fn set_payment_existential_deposit(deposit: u64) {
+	PAYMENT_EXISTENTIAL_DEPOSIT.with(|v| *v.borrow_mut() = deposit);
+}
+
 #[derive(Clone, Eq, PartialEq)]
 pub struct Test;
 parameter_types! {
@@ -31,13 +277,38 @@ parameter_types! {
 	pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
 
 	pub const ExistentialDeposit: u64 = 1;
-	pub const MinKeepBlockNumber: u64 = 1;
 	pub const MaxKeepBlockNumber: u64 = 60 * 60 / 6 * 24 * 365;
 	pub const MinimumPrice: u64 = 1;
 	pub const MinimumVotingLock: u64 = 1;
 	pub const FixRate: f64 = 0.2;
 	pub const ProfitRate: f64 = 0.2;
 	pub const DayBlockNum: u64 = 60 * 60 / 6 * 24;
+	pub const MaxAutoSettlements: u32 = 2;
+	pub const PlatformFeeRate: Permill = Permill::from_percent(5);
+	pub const FeeAccount: u64 = 99;
+	pub const DividendRate: Permill = Permill::from_percent(10);
+	pub const DividendAccount: u64 = 98;
+	pub const MaxOrderPrice: u64 = 1_000_000;
+	pub const AntiSnipeWindow: u64 = 5;
+	pub const MaxOrdersPerAccount: u32 = 10;
+	pub const MaxShareStock: u64 = 1 << 62;
+	pub const MaxAutoRelists: u32 = 2;
+	pub const MinVoteLockBlocks: u64 = 10;
+	pub const MaxBidHistory: u32 = 5;
+	pub const SettlementReward: Permill = Permill::from_percent(1);
+	pub const CancelSettlementReward: u64 = 2;
+	pub const MaxProvenanceEntries: u32 = 10;
+	pub const FeeAdmin: u64 = 1;
+	pub const MaxBundleSize: u32 = 5;
+	pub const TreasuryAccount: u64 = 96;
+	pub const IdExhaustionThreshold: u32 = 10;
+	pub const BuyoutCancelPenalty: Permill = Permill::from_percent(10);
+	pub const MaxUrlLength: u32 = 64;
+	pub const EscrowModuleId: ModuleId = ModuleId(*b"nft/escr");
+	pub const CancelPenalty: Permill = Permill::from_percent(10);
+	pub const MaxSettlementBatch: u32 = 5;
+	pub const MaxCollectionSize: u32 = 3;
+	pub const CollectionCloseBatch: u32 = 1;
 }
 impl system::Trait for Test {
 	type BaseCallFilter = ();
@@ -75,7 +346,24 @@ impl pallet_balances::Trait for Test {
 	type AccountStore = system::Module<Test>;
 	type WeightInfo = ();
 }
-type Balances = pallet_balances::Module<Test>;
+pub type Balances = pallet_balances::Module<Test>;
+// 与原生货币不同的支付货币
+impl pallet_balances::Trait<pallet_balances::Instance1> for Test {
+	type Balance = u64;
+	type MaxLocks = ();
+	type Event = TestEvent;
+	type DustRemoval = ();
+	type ExistentialDeposit = PaymentExistentialDeposit;
+	type AccountStore = StorageMapShim<
+		pallet_balances::Account<Test, pallet_balances::Instance1>,
+		system::CallOnCreatedAccount<Test>,
+		system::CallKillAccount<Test>,
+		u64,
+		pallet_balances::AccountData<u64>,
+	>;
+	type WeightInfo = ();
+}
+pub type PaymentBalances = pallet_balances::Module<Test, pallet_balances::Instance1>;
 
 impl Trait for Test {
 	type Event = TestEvent;
@@ -83,12 +371,57 @@ impl Trait for Test {
 	type MaxKeepBlockNumber = MaxKeepBlockNumber;
 	type MinimumPrice = MinimumPrice;
 	type MinimumVotingLock = MinimumVotingLock;
-	type FixRate = ();
-	type ProfitRate = ();
-	type DayBlockNum = ();
+	type FixRate = FixRate;
+	type ProfitRate = ProfitRate;
+	type DayBlockNum = DayBlockNum;
+	type MaxAutoSettlements = MaxAutoSettlements;
+	type PlatformFeeRate = PlatformFeeRate;
+	type FeeAccount = FeeAccount;
+	type DividendRate = DividendRate;
+	type DividendAccount = DividendAccount;
+	type VoterConsolation = VoterConsolation;
+	type MaxOrderPrice = MaxOrderPrice;
+	type AntiSnipeWindow = AntiSnipeWindow;
+	type MaxOrdersPerAccount = MaxOrdersPerAccount;
+	type MintLockPeriod = MintLockPeriod;
+	type MaxShareStock = MaxShareStock;
+	type MaxAutoRelists = MaxAutoRelists;
+	type MinVoteLockBlocks = MinVoteLockBlocks;
+	type MaxBidHistory = MaxBidHistory;
+	type SettlementReward = SettlementReward;
+	type CancelSettlementReward = CancelSettlementReward;
+	type MaxProvenanceEntries = MaxProvenanceEntries;
+	type MaxQueuedBids = MaxQueuedBids;
+	type ListingDeposit = ListingDeposit;
+	type FeeAdmin = FeeAdmin;
+	type MaxBundleSize = MaxBundleSize;
+	type PriceTick = PriceTick;
+	type TreasuryAccount = TreasuryAccount;
+	type UniqueUrls = UniqueUrls;
+	type IdExhaustionThreshold = IdExhaustionThreshold;
+	type BuyoutCooldown = BuyoutCooldown;
+	type BuyoutCancelPenalty = BuyoutCancelPenalty;
+	type MaxReservedPerAccount = MaxReservedPerAccount;
+	type MaxOrdersPerBlock = MaxOrdersPerBlock;
+	type MaxUrlLength = MaxUrlLength;
+	type RelistCooldown = RelistCooldown;
+	type AllowSellerVote = AllowSellerVote;
+	type UseEscrow = UseEscrow;
+	type EscrowModuleId = EscrowModuleId;
+	type CancelPenalty = CancelPenalty;
+	type MaxSettlementBatch = MaxSettlementBatch;
+	type MinVotersForPayout = MinVotersForPayout;
+	type MaxListingsPerNft = MaxListingsPerNft;
+	type DaysPerYear = DaysPerYear;
+	type MaxTotalVotesPerOrder = MaxTotalVotesPerOrder;
+	type HonorFullVoteLock = HonorFullVoteLock;
+	type CancellationFee = CancellationFee;
+	type MaxCollectionSize = MaxCollectionSize;
+	type CollectionCloseBatch = CollectionCloseBatch;
 	type NftId = u32;
 	type OrderId = u32;
 	type Currency = Balances;
+	type PaymentCurrency = PaymentBalances;
 }
 
 pub type NftModule = Module<Test>;
@@ -100,7 +433,12 @@ pub fn new_test_ext() -> sp_io::TestExternalities {
 		.build_storage::<Test>()
 		.unwrap();
 	pallet_balances::GenesisConfig::<Test> {
-		balances: vec![(1, 10000), (2, 11000), (3, 12000), (4, 13000), (5, 14000)],
+		balances: vec![(1, 1000), (2, 1000), (3, 1000), (4, 1000), (5, 1000)],
+	}
+		.assimilate_storage(&mut t)
+		.unwrap();
+	pallet_balances::GenesisConfig::<Test, pallet_balances::Instance1> {
+		balances: vec![(1, 10000), (2, 11000), (3, 12000), (4, 13000), (5, 14000), (FeeAccount::get(), 1), (DividendAccount::get(), 1)],
 	}
 		.assimilate_storage(&mut t)
 		.unwrap();
@@ -6,8 +6,8 @@ use frame_support::{assert_ok, assert_noop};
 fn test_ntf_create() {
 	new_test_ext().execute_with(|| {
 		run_to_block(10);
-		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into()));
-		let lock_event = TestEvent::nft_event(RawEvent::NftCreated(1, 0));
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		let lock_event = TestEvent::nft_event(RawEvent::NftCreated(1, 0, [0u8; 32]));
 		assert!(System::events().iter().any(|a| a.event == lock_event));
 		assert!(Nfts::<Test>::get(&0).is_some());
 		assert_eq!(NftAccount::<Test>::get(&0), 1);
@@ -18,7 +18,7 @@ fn test_ntf_create() {
 fn test_ntf_remove_success() {
 	new_test_ext().execute_with(|| {
 		run_to_block(10);
-		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into()));
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
 		assert_ok!(NftModule::remove(Origin::signed(1), 0));
 
 		let lock_event = TestEvent::nft_event(RawEvent::NftRemove(1, 0));
@@ -40,7 +40,7 @@ fn test_ntf_remove_not_exist() {
 fn test_ntf_remove_not_owner() {
 	new_test_ext().execute_with(|| {
 		run_to_block(10);
-		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into()));
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
 		assert_noop!(NftModule::remove(Origin::signed(2), 0), Error::<Test>::NotNftOwner);
 	});
 }
@@ -49,8 +49,8 @@ fn test_ntf_remove_not_owner() {
 fn test_nft_remove_order_exist() {
 	new_test_ext().execute_with(|| {
 		run_to_block(10);
-		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into()));
-		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 200, 200));
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 200, 200, SellParams::default()));
 		assert_noop!(NftModule::remove(Origin::signed(1), 0), Error::<Test>::NftOrderExist);
 	});
 }
@@ -59,7 +59,7 @@ fn test_nft_remove_order_exist() {
 fn test_ntf_transfer_success() {
 	new_test_ext().execute_with(|| {
 		run_to_block(10);
-		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into()));
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
 		assert_ok!(NftModule::transfer(Origin::signed(1), 2, 0));
 
 		let lock_event = TestEvent::nft_event(RawEvent::NftTransfer(1, 2,0));
@@ -81,7 +81,7 @@ fn test_ntf_transfer_not_exist() {
 fn test_ntf_transfer_not_owner() {
 	new_test_ext().execute_with(|| {
 		run_to_block(10);
-		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into()));
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
 		assert_noop!(NftModule::transfer(Origin::signed(2), 3, 0), Error::<Test>::NotNftOwner);
 	});
 }
@@ -90,8 +90,8 @@ fn test_ntf_transfer_not_owner() {
 fn test_nft_transfer_order_exist() {
 	new_test_ext().execute_with(|| {
 		run_to_block(10);
-		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into()));
-		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 200, 200));
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 200, 200, SellParams::default()));
 		assert_noop!(NftModule::transfer(Origin::signed(1), 2, 0), Error::<Test>::NftOrderExist);
 	});
 }
@@ -100,8 +100,8 @@ fn test_nft_transfer_order_exist() {
 fn test_order_sell_success() {
 	new_test_ext().execute_with(|| {
 		run_to_block(10);
-		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into()));
-		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 200, 200));
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 200, 200, SellParams::default()));
 		let order_opt: Option<OrderOf<Test>> = Orders::<Test>::get(&0);
 		assert!(order_opt.is_some());
 		let order = order_opt.unwrap();
@@ -111,7 +111,29 @@ fn test_order_sell_success() {
 		assert_eq!(order.end_price, 200);
 		assert_eq!(order.keep_block_num, 200);
 		assert_eq!(order.nft_id, 0);
+		assert_eq!(order.expire_block, 210);
+		assert_eq!(order.kind, AuctionKind::English);
 		assert_eq!(NftOrder::<Test>::get(&0), Some(0));
+		let sell_event = TestEvent::nft_event(RawEvent::OrderSell(1, 0, 210));
+		assert!(System::events().iter().any(|a| a.event == sell_event));
+	});
+}
+
+#[test]
+fn test_order_sell_expire_block() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 200, 50, SellParams::default()));
+		let order = Orders::<Test>::get(&0).unwrap();
+		assert_eq!(order.expire_block, order.create_block + order.keep_block_num);
+
+		run_to_block(60);
+		// 未到结算时间时按实际读取的权重收费, mock中DbWeight为0
+		assert_noop!(NftModule::order_settlement(Origin::signed(2), 0), Error::<Test>::IsNotTimeToSettlement.with_weight(0));
+		// 不经过on_initialize, 直接手动结算
+		System::set_block_number(61);
+		assert_ok!(NftModule::order_settlement(Origin::signed(2), 0));
 	});
 }
 
@@ -119,11 +141,2951 @@ fn test_order_sell_success() {
 fn test_order_buy_success() {
 	new_test_ext().execute_with(|| {
 		run_to_block(10);
-		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into()));
-		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 200, 10000));
-		assert_ok!(NftModule::order_buy(Origin::signed(2), 0, 200));
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 200, 10000, SellParams::default()));
+		assert_ok!(NftModule::order_buy(Origin::signed(2), 0, 200, None));
+		assert!(Orders::<Test>::get(&0).is_none());
+		assert!(NftOrder::<Test>::get(&0).is_none());
+		assert_eq!(NftAccount::<Test>::get(&0), 2);
+	});
+}
+
+#[test]
+fn test_order_buy_now_event() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 200, 10000, SellParams::default()));
+		// 出价高于结拍价格时按结拍价格成交
+		assert_ok!(NftModule::order_buy(Origin::signed(2), 0, 300, None));
+		let buy_now_event = TestEvent::nft_event(RawEvent::OrderBuyNow(2, 0, 200));
+		assert!(System::events().iter().any(|a| a.event == buy_now_event));
+		let complete_event = TestEvent::nft_event(RawEvent::OrderComplete(2, 0));
+		assert!(System::events().iter().any(|a| a.event == complete_event));
+	});
+}
+#[test]
+fn test_order_auto_settlement() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 200, 10, SellParams::default()));
+		assert_eq!(ExpiringOrders::<Test>::get(21), vec![0]);
+		assert_ok!(NftModule::order_buy(Origin::signed(2), 0, 150, None));
+
+		run_to_block(20);
+		assert!(Orders::<Test>::get(&0).is_some());
+
+		run_to_block(21);
 		assert!(Orders::<Test>::get(&0).is_none());
 		assert!(NftOrder::<Test>::get(&0).is_none());
+		assert!(Bids::<Test>::get(&0).is_none());
+		assert!(ExpiringOrders::<Test>::get(21).is_empty());
 		assert_eq!(NftAccount::<Test>::get(&0), 2);
+		let complete_event = TestEvent::nft_event(RawEvent::OrderComplete(2, 0));
+		assert!(System::events().iter().any(|a| a.event == complete_event));
+	});
+}
+
+#[test]
+fn test_order_auto_settlement_cancel_and_carry_over() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		for _ in 0..3 {
+			assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		}
+		for nft_id in 0..3 {
+			assert_ok!(NftModule::order_sell(Origin::signed(1), nft_id, 100, 200, 10, SellParams::default()));
+		}
+
+		// 每个区块最多结算两个订单, 第三个顺延到下一个区块
+		run_to_block(21);
+		assert!(Orders::<Test>::get(&0).is_none());
+		assert!(Orders::<Test>::get(&1).is_none());
+		assert!(Orders::<Test>::get(&2).is_some());
+		assert_eq!(ExpiringOrders::<Test>::get(22), vec![2]);
+
+		run_to_block(22);
+		assert!(Orders::<Test>::get(&2).is_none());
+		let cancel_event = TestEvent::nft_event(RawEvent::OrderCancel(1, 2));
+		assert!(System::events().iter().any(|a| a.event == cancel_event));
+		assert_eq!(NftAccount::<Test>::get(&2), 1);
+	});
+}
+
+#[test]
+fn test_order_complete_platform_fee() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 210, 10000, SellParams::default()));
+		assert_ok!(NftModule::order_buy(Origin::signed(2), 0, 210, None));
+		// 5%的手续费向下取整: 210 * 5% = 10.5 -> 10, 剩余200归卖家
+		assert_eq!(PaymentBalances::free_balance(99), 1 + 10);
+		assert_eq!(PaymentBalances::free_balance(1), 10000 + 200);
+		assert_eq!(PaymentBalances::free_balance(2), 11000 - 210);
+	});
+}
+
+#[test]
+fn test_vote_order_merge_same_account() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 200, 100, SellParams::default()));
+		assert_ok!(NftModule::vote_order(Origin::signed(2), 0, 100));
+		run_to_block(60);
+		assert_ok!(NftModule::vote_order(Origin::signed(2), 0, 300));
+
+		let votes = Votes::<Test>::get(&0);
+		assert_eq!(votes.len(), 1);
+		assert_eq!(votes[0].owner, 2);
+		assert_eq!(votes[0].amount, 400);
+		// (100 * 100 + 300 * 50) / 400 = 62
+		assert_eq!(votes[0].keep_block_num, 62);
+		assert_eq!(PaymentBalances::reserved_balance(2), 400);
+	});
+}
+
+#[test]
+fn test_owned_nfts_index() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_eq!(NftModule::owned_nfts(1), vec![0, 1]);
+
+		assert_ok!(NftModule::transfer(Origin::signed(1), 2, 0));
+		assert_eq!(NftModule::owned_nfts(1), vec![1]);
+		assert_eq!(NftModule::owned_nfts(2), vec![0]);
+
+		assert_ok!(NftModule::order_sell(Origin::signed(2), 0, 100, 200, 10000, SellParams::default()));
+		assert_ok!(NftModule::order_buy(Origin::signed(3), 0, 200, None));
+		assert!(NftModule::owned_nfts(2).is_empty());
+		assert!(!OwnedNfts::<Test>::contains_key(2));
+		assert_eq!(NftModule::owned_nfts(3), vec![0]);
+
+		assert_ok!(NftModule::remove(Origin::signed(1), 1));
+		assert!(NftModule::owned_nfts(1).is_empty());
+	});
+}
+
+#[test]
+fn test_order_complete_with_reserved_bid_only() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 200, 10, SellParams::default()));
+		// 竞价者的全部余额都被锁定, 可用余额为0
+		let _ = PaymentBalances::make_free_balance_be(&6, 150);
+		assert_ok!(NftModule::order_buy(Origin::signed(6), 0, 150, None));
+		assert_eq!(PaymentBalances::free_balance(6), 0);
+		assert_eq!(PaymentBalances::reserved_balance(6), 150);
+
+		System::set_block_number(21);
+		assert_ok!(NftModule::order_settlement(Origin::signed(2), 0));
+		assert_eq!(NftAccount::<Test>::get(&0), 6);
+		assert_eq!(PaymentBalances::reserved_balance(6), 0);
+		// 150 * 5% = 7.5 -> 7
+		assert_eq!(PaymentBalances::free_balance(99), 1 + 7);
+		// 结算奖励 150 * 1% = 1.5 -> 1
+		assert_eq!(PaymentBalances::free_balance(2), 11000 + 1);
+		assert_eq!(PaymentBalances::free_balance(1), 10000 + 142);
+	});
+}
+
+#[test]
+fn test_order_buy_raise_own_bid_reserves_delta() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 1000, 10000, SellParams::default()));
+		let _ = PaymentBalances::make_free_balance_be(&6, 160);
+		assert_ok!(NftModule::order_buy(Origin::signed(6), 0, 150, None));
+		// 可用余额只剩加价的部分
+		assert_eq!(PaymentBalances::free_balance(6), 10);
+		assert_ok!(NftModule::order_buy(Origin::signed(6), 0, 160, None));
+		assert_eq!(PaymentBalances::free_balance(6), 0);
+		assert_eq!(PaymentBalances::reserved_balance(6), 160);
+		assert_eq!(Bids::<Test>::get(&0).unwrap().price, 160);
+	});
+}
+
+#[test]
+fn test_nft_frozen() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_noop!(NftModule::set_frozen(Origin::signed(2), 0, true), Error::<Test>::NotNftOwner);
+		assert_ok!(NftModule::set_frozen(Origin::signed(1), 0, true));
+		let frozen_event = TestEvent::nft_event(RawEvent::NftFrozenChanged(0, true));
+		assert!(System::events().iter().any(|a| a.event == frozen_event));
+
+		assert_noop!(NftModule::transfer(Origin::signed(1), 2, 0), Error::<Test>::NftFrozen);
+		assert_noop!(NftModule::order_sell(Origin::signed(1), 0, 100, 200, 200, SellParams::default()), Error::<Test>::NftFrozen);
+		assert_noop!(NftModule::remove(Origin::signed(1), 0), Error::<Test>::NftFrozen);
+
+		assert_ok!(NftModule::set_frozen(Origin::root(), 0, false));
+		assert!(!FrozenNfts::<Test>::contains_key(0));
+		assert_ok!(NftModule::transfer(Origin::signed(1), 2, 0));
+		assert_ok!(NftModule::order_sell(Origin::signed(2), 0, 100, 200, 200, SellParams::default()));
+	});
+}
+
+#[test]
+fn test_simulate_shares_matches_settlement() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 1000, 100, SellParams::default()));
+		assert_ok!(NftModule::vote_order(Origin::signed(2), 0, 200));
+		assert_ok!(NftModule::vote_order(Origin::signed(3), 0, 300));
+		assert_ok!(NftModule::order_buy(Origin::signed(5), 0, 500, None));
+
+		let simulated = NftModule::simulate_shares(0, 4, 400, 100);
+		assert_eq!(simulated.len(), 3);
+		// 预估不修改状态
+		assert_eq!(Votes::<Test>::get(&0).len(), 2);
+
+		assert_ok!(NftModule::vote_order(Origin::signed(4), 0, 400));
+		run_to_block(111);
+		assert!(Orders::<Test>::get(&0).is_none());
+
+		let actual: Vec<(u64, u128)> = vec![
+			(2, (PaymentBalances::free_balance(2) - 11000) as u128),
+			(3, (PaymentBalances::free_balance(3) - 12000) as u128),
+			(4, (PaymentBalances::free_balance(4) - 13000) as u128),
+		];
+		assert_eq!(simulated, actual);
+		assert!(actual.iter().all(|(_, amount)| *amount > 0));
+	});
+}
+
+fn test_order(keep_block_num: u64) -> OrderOf<Test> {
+	Order {
+		order_id: 0,
+		start_price: 100,
+		end_price: 1000,
+		nft_id: 0,
+		create_block: 0,
+		start_block: 0,
+		keep_block_num,
+		owner: 1,
+		expire_block: keep_block_num,
+		kind: AuctionKind::English,
+		auto_relist: false,
+		relist_count: 0,
+		deposit: 0,
+		fee_rate: None,
+		settlement_mode: SettlementMode::FirstPrice,
+		payees: vec![],
+	}
+}
+
+fn test_vote(owner: u64, amount: u64, keep_block_num: u64) -> VoteOf<Test> {
+	Vote {
+		order_id: 0,
+		amount,
+		keep_block_num,
+		owner,
+	}
+}
+
+fn assert_share(share: &(u64, U64F64), owner: u64, expected: f64) {
+	assert_eq!(share.0, owner);
+	let actual: f64 = share.1.to_num();
+	assert!((actual - expected).abs() < 1e-9 * expected.max(1.0), "share {} != {}", actual, expected);
+}
+
+#[test]
+fn test_compute_shares_empty() {
+	new_test_ext().execute_with(|| {
+		assert!(NftModule::compute_shares(&test_order(14400), 100, &[]).unwrap().is_empty());
+	});
+}
+
+#[test]
+fn test_compute_shares_floating_rate() {
+	new_test_ext().execute_with(|| {
+		// 拍卖时长1天, 初始股权数 = 100 * 0.2 / 1 * 365 = 7300
+		let votes = vec![test_vote(2, 100, 14400), test_vote(3, 200, 7200)];
+		let shares = NftModule::compute_shares(&test_order(14400), 100, &votes).unwrap();
+		assert_eq!(shares.len(), 2);
+		// 质押权重100, 之前没有质押, 汇率为1
+		assert_share(&shares[0], 2, 100.0);
+		// 质押权重 200 * 0.5 = 100, 汇率 7300 / (7300 + 100)
+		assert_share(&shares[1], 3, 100.0 * 7300.0 / 7400.0);
+	});
+}
+
+#[test]
+fn test_compute_shares_fixed_rate_crossover() {
+	new_test_ext().execute_with(|| {
+		let votes = vec![
+			test_vote(2, 10_000, 14400),
+			test_vote(3, 30_000, 14400),
+			test_vote(4, 10_000, 7200),
+		];
+		let shares = NftModule::compute_shares(&test_order(14400), 100, &votes).unwrap();
+		assert_eq!(shares.len(), 3);
+		// 第一个质押者年化 7300 / 10000 = 0.73, 汇率为1
+		assert_share(&shares[0], 2, 10_000.0);
+		// 第二个质押者的年化收益率已低于固定年化, 后续质押者沿用第二个质押者的汇率
+		let rate = 7300.0 / (7300.0 + 10_000.0);
+		assert_share(&shares[1], 3, 30_000.0 * rate);
+		assert_share(&shares[2], 4, 5_000.0 * rate);
+	});
+}
+
+#[test]
+fn test_compute_shares_two_voters_year_rate() {
+	new_test_ext().execute_with(|| {
+		// 拍卖时长1天, 初始股权数 = 100 * 0.2 / 1 * 365 = 7300
+		let votes = vec![test_vote(2, 100, 14400), test_vote(3, 100, 14400)];
+		let shares = NftModule::compute_shares(&test_order(14400), 100, &votes).unwrap();
+		let t1: f64 = shares[0].1.to_num();
+		let t2: f64 = shares[1].1.to_num();
+		// 年化收益率 = t / tt * 初始股权数 / 质押权重
+		let year_rate_1 = t1 / t1 * 7300.0 / 100.0;
+		let year_rate_2 = t2 / (t1 + t2) * 7300.0 / 100.0;
+		// 第一个质押者: 汇率1, 独享全部股权, 年化 7300 / 100 = 73
+		assert!((year_rate_1 - 73.0).abs() < 1e-9);
+		// 第二个质押者: 汇率 7300 / 7400, 凭证占比 7300 / 14700, 年化 73 * 7300 / 14700
+		assert!((year_rate_2 - 73.0 * 7300.0 / 14700.0).abs() < 1e-9);
+	});
+}
+
+#[test]
+fn test_share_payouts() {
+	new_test_ext().execute_with(|| {
+		let votes = vec![test_vote(2, 100, 14400), test_vote(3, 200, 7200)];
+		let shares = NftModule::compute_shares(&test_order(14400), 1000, &votes).unwrap();
+		let payouts = NftModule::share_payouts(1000, &shares).unwrap();
+		// 总利润 1000 * 0.2 = 200, 按凭证比例分配并向下取整
+		assert_eq!(payouts.len(), 2);
+		assert!(payouts.iter().map(|(_, amount)| *amount).sum::<u64>() <= 200);
+		assert!(payouts[0].1 > payouts[1].1);
+	});
+}
+
+#[test]
+fn test_share_payouts_overflow() {
+	new_test_ext().execute_with(|| {
+		// 凭证总数溢出时返回错误, 不会panic
+		let shares = vec![(2, U64F64::max_value()), (3, U64F64::max_value())];
+		assert_eq!(NftModule::share_payouts(1000, &shares), Err(Error::<Test>::ShareOverflow.into()));
+	});
+}
+
+#[test]
+fn test_orders_paged() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		for nft_id in 0..10 {
+			assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+			assert_ok!(NftModule::order_sell(Origin::signed(1), nft_id, 100, 200, 200, SellParams::default()));
+		}
+
+		let mut seen: Vec<u32> = vec![];
+		let mut cursor: Option<u32> = None;
+		let mut pages = 0;
+		loop {
+			let (page, next) = NftModule::orders_paged(cursor, 4);
+			assert!(page.len() <= 4);
+			for (order_id, order) in page {
+				assert_eq!(order.order_id, order_id);
+				assert!(!seen.contains(&order_id));
+				seen.push(order_id);
+			}
+			pages += 1;
+			if next.is_none() {
+				break;
+			}
+			cursor = next;
+		}
+		assert_eq!(pages, 3);
+		seen.sort();
+		assert_eq!(seen, (0..10).collect::<Vec<u32>>());
+	});
+}
+
+// nft 0 第一次成交, voters 质押后由账户3以500竞价成交, 质押者获得分红股份
+fn sell_with_voters(voters: Vec<(u64, u64)>) {
+	assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+	assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 1000, 100, SellParams::default()));
+	for (who, amount) in voters {
+		assert_ok!(NftModule::vote_order(Origin::signed(who), 0, amount));
+	}
+	assert_ok!(NftModule::order_buy(Origin::signed(3), 0, 500, None));
+	run_to_block(System::block_number() + 101);
+	assert_eq!(NftAccount::<Test>::get(&0), 3);
+}
+
+#[test]
+fn test_claim_dividend_single_shareholder() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		sell_with_voters(vec![(2, 200)]);
+		assert!(NftModule::nft_shares(0, 2) > 0);
+		assert_eq!(NftTotalShares::<Test>::get(0), NftModule::nft_shares(0, 2));
+		assert_noop!(NftModule::claim_dividend(Origin::signed(2), 0), Error::<Test>::NoDividend);
+
+		// 第二次成交, 成交价的10%注入分红池
+		assert_ok!(NftModule::order_sell(Origin::signed(3), 0, 100, 1000, 100, SellParams::default()));
+		let seller_balance = PaymentBalances::free_balance(3);
+		assert_ok!(NftModule::order_buy(Origin::signed(4), 0, 1000, None));
+		assert_eq!(DividendPool::<Test>::get(0), 100);
+		assert_eq!(NftModule::dividends(0, 2), 100);
+		// 卖家获得 1000 - 5%手续费 - 10%分红
+		assert_eq!(PaymentBalances::free_balance(3), seller_balance + 850);
+
+		let balance = PaymentBalances::free_balance(2);
+		assert_ok!(NftModule::claim_dividend(Origin::signed(2), 0));
+		assert_eq!(PaymentBalances::free_balance(2), balance + 100);
+		assert_eq!(DividendPool::<Test>::get(0), 0);
+		assert_eq!(NftModule::dividends(0, 2), 0);
+		let claim_event = TestEvent::nft_event(RawEvent::DividendClaimed(2, 0, 100));
+		assert!(System::events().iter().any(|a| a.event == claim_event));
+	});
+}
+
+#[test]
+fn test_claim_dividend_multi_shareholder() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		sell_with_voters(vec![(2, 200), (5, 400)]);
+		let shares_2 = NftModule::nft_shares(0, 2);
+		let shares_5 = NftModule::nft_shares(0, 5);
+		assert!(shares_2 > 0 && shares_5 > shares_2);
+
+		assert_ok!(NftModule::order_sell(Origin::signed(3), 0, 100, 1000, 100, SellParams::default()));
+		assert_ok!(NftModule::order_buy(Origin::signed(4), 0, 1000, None));
+		let total = shares_2 + shares_5;
+		let expected_2 = 100 * shares_2 / total;
+		let expected_5 = 100 * shares_5 / total;
+		assert_eq!(DividendPool::<Test>::get(0), expected_2 + expected_5);
+
+		let balance_2 = PaymentBalances::free_balance(2);
+		let balance_5 = PaymentBalances::free_balance(5);
+		assert_ok!(NftModule::claim_dividend(Origin::signed(2), 0));
+		assert_ok!(NftModule::claim_dividend(Origin::signed(5), 0));
+		assert_eq!(PaymentBalances::free_balance(2), balance_2 + expected_2);
+		assert_eq!(PaymentBalances::free_balance(5), balance_5 + expected_5);
+		assert_eq!(DividendPool::<Test>::get(0), 0);
+	});
+}
+
+#[test]
+fn test_voter_consolation_on_unsold_expiry() {
+	new_test_ext().execute_with(|| {
+		set_voter_consolation(Permill::from_percent(10));
+		let _ = PaymentBalances::make_free_balance_be(&99, 10000);
+		run_to_block(10);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 200, 100, SellParams::default()));
+		// 质押满整个拍卖周期, 权重 200
+		assert_ok!(NftModule::vote_order(Origin::signed(2), 0, 200));
+		run_to_block(60);
+		// 只质押半个拍卖周期, 权重 300 * 50 / 100 = 150
+		assert_ok!(NftModule::vote_order(Origin::signed(3), 0, 300));
+
+		run_to_block(111);
+		assert!(Orders::<Test>::get(&0).is_none());
+		assert_eq!(PaymentBalances::reserved_balance(2), 0);
+		assert_eq!(PaymentBalances::reserved_balance(3), 0);
+		assert_eq!(PaymentBalances::free_balance(2), 11000 + 20);
+		assert_eq!(PaymentBalances::free_balance(3), 12000 + 15);
+		assert_eq!(PaymentBalances::free_balance(99), 10000 - 35);
+		set_voter_consolation(Permill::zero());
+	});
+}
+
+#[test]
+fn test_order_buy_now_refunds_previous_bid() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 200, 10000, SellParams::default()));
+		assert_ok!(NftModule::order_buy(Origin::signed(2), 0, 150, None));
+		assert_eq!(PaymentBalances::reserved_balance(2), 150);
+
+		assert_ok!(NftModule::order_buy(Origin::signed(3), 0, 200, None));
+		assert!(Bids::<Test>::get(&0).is_none());
+		assert_eq!(PaymentBalances::reserved_balance(2), 0);
+		assert_eq!(PaymentBalances::free_balance(2), 11000);
+		assert_eq!(PaymentBalances::reserved_balance(3), 0);
+		assert_eq!(PaymentBalances::free_balance(3), 12000 - 200);
+		assert_eq!(NftAccount::<Test>::get(&0), 3);
+	});
+}
+
+#[test]
+fn test_vote_order_keeps_existential_deposit() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 200, 100, SellParams::default()));
+		assert_noop!(NftModule::vote_order(Origin::signed(2), 0, 11000), Error::<Test>::InsufficientBalanceForVote);
+		assert_ok!(NftModule::vote_order(Origin::signed(2), 0, 10999));
+		assert_eq!(PaymentBalances::free_balance(2), 1);
+	});
+}
+
+#[test]
+fn test_extend_order_success() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 200, 100, SellParams::default()));
+		assert_ok!(NftModule::vote_order(Origin::signed(2), 0, 100));
+		assert_noop!(NftModule::extend_order(Origin::signed(2), 0, 50), Error::<Test>::NotNftOwner);
+		assert_ok!(NftModule::extend_order(Origin::signed(1), 0, 50));
+
+		let order = Orders::<Test>::get(&0).unwrap();
+		assert_eq!(order.keep_block_num, 150);
+		assert_eq!(order.expire_block, 160);
+		assert_eq!(Votes::<Test>::get(&0)[0].keep_block_num, 150);
+		let extend_event = TestEvent::nft_event(RawEvent::OrderExtended(1, 0, 160));
+		assert!(System::events().iter().any(|a| a.event == extend_event));
+
+		// 原到期区块不再自动结算, 新的到期区块自动结算
+		run_to_block(111);
+		assert!(Orders::<Test>::get(&0).is_some());
+		run_to_block(161);
+		assert!(Orders::<Test>::get(&0).is_none());
+	});
+}
+
+#[test]
+fn test_extend_order_too_big() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 200, 100, SellParams::default()));
+		let max = MaxKeepBlockNumber::get();
+		assert_noop!(NftModule::extend_order(Origin::signed(1), 0, max), Error::<Test>::KeepBlockNumTooBig);
+		assert_noop!(NftModule::extend_order(Origin::signed(1), 0, u64::max_value()), Error::<Test>::KeepBlockNumTooBig);
+		assert_ok!(NftModule::extend_order(Origin::signed(1), 0, max - 100));
+
+		System::set_block_number(max + 11);
+		assert_noop!(NftModule::extend_order(Origin::signed(1), 0, 1), Error::<Test>::IsTimeToSettlement);
+	});
+}
+
+#[test]
+fn test_order_detail() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_eq!(NftModule::order_detail(0), None);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 200, 100, SellParams::default()));
+		assert_ok!(NftModule::order_buy(Origin::signed(2), 0, 150, None));
+		assert_ok!(NftModule::vote_order(Origin::signed(3), 0, 100));
+		assert_ok!(NftModule::vote_order(Origin::signed(4), 0, 300));
+
+		let detail = NftModule::order_detail(0).unwrap();
+		assert_eq!(detail.order, Orders::<Test>::get(&0).unwrap());
+		assert_eq!(detail.bid, Some(Bid { order_id: 0, price: 150, owner: 2 }));
+		assert_eq!(detail.total_votes, 400);
+		assert_eq!(detail.voter_count, 2);
 	});
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_order_sell_max_price() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		let max = MaxOrderPrice::get();
+		assert_noop!(NftModule::order_sell(Origin::signed(1), 0, 100, max + 1, 100, SellParams::default()), Error::<Test>::OrderPriceTooBig);
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, max, 100, SellParams::default()));
+		assert_eq!(Orders::<Test>::get(&0).unwrap().end_price, max);
+	});
+}
+
+#[test]
+fn test_order_buy_anti_snipe_extension() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 200, 100, SellParams::default()));
+		// 离到期还有10个区块, 不延期
+		run_to_block(100);
+		assert_ok!(NftModule::order_buy(Origin::signed(2), 0, 150, None));
+		assert_eq!(Orders::<Test>::get(&0).unwrap().expire_block, 110);
+
+		// 离到期还有3个区块, 延长到剩余5个区块
+		run_to_block(107);
+		assert_ok!(NftModule::order_buy(Origin::signed(3), 0, 160, None));
+		let order = Orders::<Test>::get(&0).unwrap();
+		assert_eq!(order.expire_block, 112);
+		assert_eq!(order.keep_block_num, 102);
+		let extend_event = TestEvent::nft_event(RawEvent::OrderExtended(3, 0, 112));
+		assert!(System::events().iter().any(|a| a.event == extend_event));
+
+		run_to_block(112);
+		assert!(Orders::<Test>::get(&0).is_some());
+		run_to_block(113);
+		assert!(Orders::<Test>::get(&0).is_none());
+		assert_eq!(NftAccount::<Test>::get(&0), 3);
+	});
+}
+
+#[test]
+fn test_order_buy_anti_snipe_capped_by_max_keep() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		let max = MaxKeepBlockNumber::get();
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 200, max, SellParams::default()));
+		System::set_block_number(max + 9);
+		assert_ok!(NftModule::order_buy(Origin::signed(2), 0, 150, None));
+		let order = Orders::<Test>::get(&0).unwrap();
+		assert_eq!(order.expire_block, max + 10);
+		assert_eq!(order.keep_block_num, max);
+	});
+}
+
+#[test]
+fn test_payment_currency_is_separate_from_native() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 1000, 100, SellParams::default()));
+		assert_ok!(NftModule::vote_order(Origin::signed(2), 0, 200));
+		assert_ok!(NftModule::order_buy(Origin::signed(3), 0, 500, None));
+		assert_eq!(PaymentBalances::reserved_balance(2), 200);
+		assert_eq!(PaymentBalances::reserved_balance(3), 500);
+		assert_eq!(Balances::reserved_balance(3), 0);
+
+		run_to_block(111);
+		assert_eq!(NftAccount::<Test>::get(&0), 3);
+		assert_eq!(PaymentBalances::free_balance(3), 12000 - 500);
+		// 原生货币不受拍卖影响
+		for who in 1..=5 {
+			assert_eq!(Balances::free_balance(who), 1000);
+			assert_eq!(Balances::reserved_balance(who), 0);
+		}
+	});
+}
+
+#[test]
+fn test_active_orders_index() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert!(NftModule::active_orders().is_empty());
+		for _ in 0..2 {
+			assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		}
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 200, 10, SellParams::default()));
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 1, 100, 200, 20, SellParams::default()));
+		assert_ok!(NftModule::order_buy(Origin::signed(2), 0, 150, None));
+		let mut active = NftModule::active_orders();
+		active.sort();
+		assert_eq!(active, vec![0, 1]);
+
+		// 订单0成交后移出索引
+		run_to_block(21);
+		assert_eq!(NftModule::active_orders(), vec![1]);
+		// 订单1流拍后移出索引
+		run_to_block(31);
+		assert!(NftModule::active_orders().is_empty());
+	});
+}
+
+#[test]
+fn test_max_orders_per_account() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		let max = MaxOrdersPerAccount::get();
+		for nft_id in 0..=max {
+			assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+			if nft_id < max {
+				assert_ok!(NftModule::order_sell(Origin::signed(1), nft_id, 100, 200, 10 + nft_id as u64, SellParams::default()));
+			}
+		}
+		assert_eq!(OrderCountByOwner::<Test>::get(1), max);
+		assert_noop!(NftModule::order_sell(Origin::signed(1), max, 100, 200, 100, SellParams::default()), Error::<Test>::TooManyOrders);
+
+		// 第一个订单流拍后可以再次挂单
+		run_to_block(21);
+		assert_eq!(OrderCountByOwner::<Test>::get(1), max - 1);
+		assert_ok!(NftModule::order_sell(Origin::signed(1), max, 100, 200, 100, SellParams::default()));
+		assert_eq!(OrderCountByOwner::<Test>::get(1), max);
+	});
+}
+
+#[test]
+fn test_settleable_orders() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		for _ in 0..2 {
+			assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		}
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 200, 10, SellParams::default()));
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 1, 100, 200, 20, SellParams::default()));
+		assert!(NftModule::settleable_orders().is_empty());
+
+		System::set_block_number(21);
+		assert_eq!(NftModule::settleable_orders(), vec![0]);
+		assert_ok!(NftModule::order_settlement(Origin::signed(2), 0));
+		assert!(NftModule::settleable_orders().is_empty());
+	});
+}
+
+#[test]
+fn test_order_sell_zero_start_price() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_noop!(NftModule::order_sell(Origin::signed(1), 0, 0, 200, 100, SellParams::default()), Error::<Test>::OrderPriceTooSmall);
+	});
+}
+
+#[test]
+fn test_reduce_vote() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 200, 100, SellParams::default()));
+		assert_ok!(NftModule::vote_order(Origin::signed(2), 0, 300));
+
+		assert_ok!(NftModule::reduce_vote(Origin::signed(2), 0, 100));
+		assert_eq!(Votes::<Test>::get(&0)[0].amount, 200);
+		assert_eq!(PaymentBalances::reserved_balance(2), 200);
+
+		assert_ok!(NftModule::reduce_vote(Origin::signed(2), 0, 200));
+		assert!(Votes::<Test>::get(&0).is_empty());
+		assert_eq!(PaymentBalances::reserved_balance(2), 0);
+		assert_eq!(PaymentBalances::free_balance(2), 11000);
+	});
+}
+
+#[test]
+fn test_reduce_vote_too_large() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 200, 100, SellParams::default()));
+		assert_noop!(NftModule::reduce_vote(Origin::signed(2), 0, 1), Error::<Test>::ReduceTooLarge);
+		assert_ok!(NftModule::vote_order(Origin::signed(2), 0, 300));
+		assert_noop!(NftModule::reduce_vote(Origin::signed(2), 0, 301), Error::<Test>::ReduceTooLarge);
+
+		System::set_block_number(111);
+		assert_noop!(NftModule::reduce_vote(Origin::signed(2), 0, 100), Error::<Test>::IsTimeToSettlement);
+	});
+}
+
+#[test]
+fn test_mint_lock_period() {
+	new_test_ext().execute_with(|| {
+		set_mint_lock_period(20);
+		run_to_block(10);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_eq!(NftMintBlock::<Test>::get(0), 10);
+		assert_noop!(NftModule::transfer(Origin::signed(1), 2, 0), Error::<Test>::NftLocked);
+		assert_noop!(NftModule::order_sell(Origin::signed(1), 0, 100, 200, 100, SellParams::default()), Error::<Test>::NftLocked);
+
+		run_to_block(29);
+		assert_noop!(NftModule::transfer(Origin::signed(1), 2, 0), Error::<Test>::NftLocked);
+		run_to_block(30);
+		assert_ok!(NftModule::transfer(Origin::signed(1), 2, 0));
+		assert_ok!(NftModule::order_sell(Origin::signed(2), 0, 100, 200, 100, SellParams::default()));
+		set_mint_lock_period(0);
+	});
+}
+
+#[test]
+fn test_fixed_price_order() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 200, 100, SellParams { kind: AuctionKind::FixedPrice, ..Default::default() }));
+		assert_eq!(Orders::<Test>::get(&0).unwrap().kind, AuctionKind::FixedPrice);
+
+		// 一口价订单不允许竞价
+		assert_noop!(NftModule::order_buy(Origin::signed(2), 0, 150, None), Error::<Test>::BiddingNotAllowed);
+		assert_noop!(NftModule::order_buy(Origin::signed(2), 0, 200, None), Error::<Test>::BiddingNotAllowed);
+
+		// 以起拍价格立即成交
+		assert_ok!(NftModule::order_buy(Origin::signed(2), 0, 100, None));
+		assert!(Orders::<Test>::get(&0).is_none());
+		assert_eq!(NftAccount::<Test>::get(&0), 2);
+		assert_eq!(PaymentBalances::free_balance(2), 11000 - 100);
+		let buy_event = TestEvent::nft_event(RawEvent::OrderBuyNow(2, 0, 100));
+		assert!(System::events().iter().any(|a| a.event == buy_event));
+	});
+}
+
+#[test]
+fn test_dutch_order_price_decays() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 300, 100, SellParams { kind: AuctionKind::Dutch, ..Default::default() }));
+		let order = Orders::<Test>::get(&0).unwrap();
+		assert_eq!(NftModule::dutch_price(&order), 300);
+
+		// 经过一半的拍卖时间, 价格降到 300 - 200 / 2 = 200
+		run_to_block(60);
+		assert_eq!(NftModule::dutch_price(&order), 200);
+		assert_noop!(NftModule::order_buy(Origin::signed(2), 0, 199, None), Error::<Test>::OrderPriceTooSmall);
+
+		// 第一个出价不低于当前价格的买家以当前价格成交
+		assert_ok!(NftModule::order_buy(Origin::signed(2), 0, 250, None));
+		assert_eq!(NftAccount::<Test>::get(&0), 2);
+		assert_eq!(PaymentBalances::free_balance(2), 11000 - 200);
+	});
+}
+
+#[test]
+fn test_compute_shares_caps_stock_for_huge_price() {
+	new_test_ext().execute_with(|| {
+		// 拍卖时长1个区块, 初始股权数 = 价格 * 0.2 * 14400 * 365, 远超U64F64范围
+		let votes = vec![test_vote(2, 100, 1), test_vote(3, 100, 1)];
+		let shares = NftModule::compute_shares(&test_order(1), u64::max_value(), &votes).unwrap();
+		assert_eq!(shares.len(), 2);
+		// 初始股权数截断为MaxShareStock, 汇率接近1
+		let stock = MaxShareStock::get() as f64;
+		assert_share(&shares[0], 2, 100.0);
+		assert_share(&shares[1], 3, 100.0 * stock / (stock + 100.0));
+		let payouts = NftModule::share_payouts(u64::max_value(), &shares).unwrap();
+		assert_eq!(payouts.len(), 2);
+	});
+}
+
+#[test]
+fn test_compute_shares_zero_weight_vote() {
+	new_test_ext().execute_with(|| {
+		let votes = vec![test_vote(2, 100, 0), test_vote(3, 100, 14400)];
+		let shares = NftModule::compute_shares(&test_order(14400), 100, &votes).unwrap();
+		assert_share(&shares[0], 3, 100.0);
+		assert_share(&shares[1], 2, 0.0);
+	});
+}
+
+#[test]
+fn test_order_auto_relist() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 200, 10, SellParams { auto_relist: true, ..Default::default() }));
+
+		// 流拍后以新的订单Id重新挂单
+		run_to_block(21);
+		assert!(Orders::<Test>::get(&0).is_none());
+		let order = Orders::<Test>::get(&1).unwrap();
+		assert_eq!(NftOrder::<Test>::get(&0), Some(1));
+		assert_eq!((order.start_price, order.end_price, order.keep_block_num), (100, 200, 10));
+		assert_eq!((order.create_block, order.expire_block, order.relist_count), (21, 31, 1));
+		let sell_event = TestEvent::nft_event(RawEvent::OrderSell(1, 1, 31));
+		assert!(System::events().iter().any(|a| a.event == sell_event));
+
+		// 达到自动重新挂单次数上限后取消订单
+		run_to_block(32);
+		assert_eq!(Orders::<Test>::get(&2).unwrap().relist_count, 2);
+		run_to_block(43);
+		assert!(Orders::<Test>::get(&3).is_none());
+		assert!(NftOrder::<Test>::get(&0).is_none());
+		let cancel_event = TestEvent::nft_event(RawEvent::OrderCancel(1, 2));
+		assert!(System::events().iter().any(|a| a.event == cancel_event));
+	});
+}
+
+#[test]
+fn test_auto_relist_rechecks_listing() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		set_listing_deposit(5);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 200, 10, SellParams { auto_relist: true, ..Default::default() }));
+		// 卖家权利转让给不持有nft的账户4
+		assert_ok!(NftModule::transfer_order_ownership(Origin::signed(1), 0, 4));
+
+		// 新卖家不是nft所有者, 不能重新挂单, 订单被取消并退还押金
+		run_to_block(21);
+		assert!(Orders::<Test>::get(&0).is_none());
+		assert!(Orders::<Test>::get(&1).is_none());
+		assert!(NftOrder::<Test>::get(&0).is_none());
+		assert_eq!(PaymentBalances::reserved_balance(4), 0);
+		assert_eq!(PaymentBalances::free_balance(4), 13000 + 5);
+		let cancel_event = TestEvent::nft_event(RawEvent::OrderCancel(4, 0));
+		assert!(System::events().iter().any(|a| a.event == cancel_event));
+	});
+}
+
+#[test]
+fn test_total_value_locked_lifecycle() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 1000, 100, SellParams::default()));
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 1, 100, 1000, 100, SellParams::default()));
+
+		assert_ok!(NftModule::vote_order(Origin::signed(2), 0, 200));
+		assert_ok!(NftModule::order_buy(Origin::signed(3), 0, 300, None));
+		// 被超过的出价退还
+		assert_ok!(NftModule::order_buy(Origin::signed(4), 0, 400, None));
+		assert_ok!(NftModule::order_buy(Origin::signed(4), 0, 500, None));
+		assert_eq!(NftModule::total_value_locked(), 200 + 500);
+
+		// 一口价成交并退还之前的出价
+		assert_ok!(NftModule::order_buy(Origin::signed(5), 1, 150, None));
+		assert_ok!(NftModule::vote_order(Origin::signed(3), 1, 100));
+		assert_ok!(NftModule::order_buy(Origin::signed(2), 1, 1000, None));
+		assert_eq!(NftModule::total_value_locked(), 200 + 500);
+
+		// 到期结算
+		run_to_block(111);
+		assert!(Orders::<Test>::get(&0).is_none());
+		assert_eq!(NftModule::total_value_locked(), 0);
+		for who in 1..=5 {
+			assert_eq!(PaymentBalances::reserved_balance(who), 0);
+		}
+	});
+}
+
+#[test]
+fn test_order_buy_max_price() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 1000, 100, SellParams::default()));
+		assert_ok!(NftModule::order_buy(Origin::signed(2), 0, 200, Some(100)));
+		// 当前最高出价已经涨到300, 超过买家可接受的250
+		assert_ok!(NftModule::order_buy(Origin::signed(3), 0, 300, None));
+		assert_noop!(NftModule::order_buy(Origin::signed(2), 0, 400, Some(250)), Error::<Test>::PriceExceedsMax);
+		assert_ok!(NftModule::order_buy(Origin::signed(2), 0, 400, Some(300)));
+	});
+}
+
+#[test]
+fn test_dutch_order_buy_max_price() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 300, 100, SellParams { kind: AuctionKind::Dutch, ..Default::default() }));
+		run_to_block(60);
+		// 当前价格200
+		assert_noop!(NftModule::order_buy(Origin::signed(2), 0, 300, Some(199)), Error::<Test>::PriceExceedsMax);
+		assert_ok!(NftModule::order_buy(Origin::signed(2), 0, 300, Some(200)));
+		assert_eq!(NftAccount::<Test>::get(&0), 2);
+	});
+}
+
+#[test]
+fn test_force_transfer() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_noop!(NftModule::force_transfer(Origin::signed(1), 1, 2, 0), DispatchError::BadOrigin);
+		assert_noop!(NftModule::force_transfer(Origin::root(), 3, 2, 0), Error::<Test>::NotNftOwner);
+
+		assert_ok!(NftModule::force_transfer(Origin::root(), 1, 2, 0));
+		assert_eq!(NftAccount::<Test>::get(&0), 2);
+		assert_eq!(NftModule::owned_nfts(2), vec![0]);
+		assert!(NftModule::owned_nfts(1).is_empty());
+		let transfer_event = TestEvent::nft_event(RawEvent::NftTransfer(1, 2, 0));
+		assert!(System::events().iter().any(|a| a.event == transfer_event));
+
+		assert_ok!(NftModule::order_sell(Origin::signed(2), 0, 100, 200, 100, SellParams::default()));
+		assert_noop!(NftModule::force_transfer(Origin::root(), 2, 3, 0), Error::<Test>::NftOrderExist);
+	});
+}
+
+#[test]
+fn test_vote_order_window_closed() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 200, 100, SellParams::default()));
+		// 剩余10个区块时可以质押
+		run_to_block(100);
+		assert_ok!(NftModule::vote_order(Origin::signed(2), 0, 100));
+		// 剩余9个区块时不允许质押
+		run_to_block(101);
+		assert_noop!(NftModule::vote_order(Origin::signed(3), 0, 100), Error::<Test>::VoteWindowClosed);
+	});
+}
+
+#[test]
+fn test_bid_history() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 1000, 100, SellParams::default()));
+		assert_ok!(NftModule::order_buy(Origin::signed(2), 0, 150, None));
+		run_to_block(11);
+		assert_ok!(NftModule::order_buy(Origin::signed(3), 0, 200, None));
+		run_to_block(12);
+		assert_ok!(NftModule::order_buy(Origin::signed(2), 0, 250, None));
+		assert_eq!(NftModule::bid_history(0), vec![(2, 150, 10), (3, 200, 11), (2, 250, 12)]);
+
+		// 超出上限时丢弃最早的记录
+		for price in 3..6 {
+			assert_ok!(NftModule::order_buy(Origin::signed(4), 0, price * 100, None));
+		}
+		let history = NftModule::bid_history(0);
+		assert_eq!(history.len(), MaxBidHistory::get() as usize);
+		assert_eq!(history[0], (3, 200, 11));
+
+		// 订单成交后清空
+		run_to_block(111);
+		assert!(NftModule::bid_history(0).is_empty());
+	});
+}
+
+#[test]
+fn test_burned_nft_error() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::remove(Origin::signed(1), 0));
+		assert!(BurnedNfts::<Test>::get(0));
+		assert_noop!(NftModule::transfer(Origin::signed(1), 2, 0), Error::<Test>::NftBurned);
+		assert_noop!(NftModule::order_sell(Origin::signed(1), 0, 100, 200, 100, SellParams::default()), Error::<Test>::NftBurned);
+		// 从未存在的nft仍然返回NftIdNotExist
+		assert_noop!(NftModule::transfer(Origin::signed(1), 2, 1), Error::<Test>::NftIdNotExist);
+	});
+}
+
+#[test]
+fn test_settlement_reward() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 1000, 10, SellParams::default()));
+		assert_ok!(NftModule::order_buy(Origin::signed(3), 0, 500, None));
+
+		System::set_block_number(21);
+		assert_ok!(NftModule::order_settlement(Origin::signed(5), 0));
+		// 结算奖励 500 * 1% = 5, 手续费 500 * 5% = 25
+		assert_eq!(PaymentBalances::free_balance(5), 14000 + 5);
+		assert_eq!(PaymentBalances::free_balance(1), 10000 + 500 - 25 - 5);
+	});
+}
+
+#[test]
+fn test_cancel_settlement_reward() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		let _ = PaymentBalances::make_free_balance_be(&FeeAccount::get(), 100);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 1000, 10, SellParams::default()));
+
+		System::set_block_number(21);
+		assert_ok!(NftModule::order_settlement(Origin::signed(5), 0));
+		assert_eq!(PaymentBalances::free_balance(5), 14000 + CancelSettlementReward::get());
+		assert_eq!(PaymentBalances::free_balance(FeeAccount::get()), 100 - CancelSettlementReward::get());
+	});
+}
+
+#[test]
+fn test_update_order_price() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 1000, 100, SellParams::default()));
+		assert_noop!(NftModule::update_order_price(Origin::signed(2), 0, 50, 500), Error::<Test>::NotNftOwner);
+		assert_noop!(NftModule::update_order_price(Origin::signed(1), 0, 600, 500), Error::<Test>::OrderPriceIllegal);
+
+		assert_ok!(NftModule::update_order_price(Origin::signed(1), 0, 50, 500));
+		let order = Orders::<Test>::get(&0).unwrap();
+		assert_eq!((order.start_price, order.end_price), (50, 500));
+		let update_event = TestEvent::nft_event(RawEvent::OrderPriceUpdated(1, 0, 50, 500));
+		assert!(System::events().iter().any(|a| a.event == update_event));
+	});
+}
+
+#[test]
+fn test_update_order_price_with_bid() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 1000, 100, SellParams::default()));
+		assert_ok!(NftModule::order_buy(Origin::signed(2), 0, 150, None));
+		assert_noop!(NftModule::update_order_price(Origin::signed(1), 0, 50, 500), Error::<Test>::OrderHasBid);
+	});
+}
+
+#[test]
+fn test_compute_shares_independent_of_vote_order() {
+	new_test_ext().execute_with(|| {
+		let votes = vec![
+			test_vote(4, 10_000, 7200),
+			test_vote(3, 30_000, 14400),
+			test_vote(2, 10_000, 14400),
+			test_vote(5, 5_000, 7200),
+		];
+		let mut reversed = votes.clone();
+		reversed.reverse();
+		let shares = NftModule::compute_shares(&test_order(14400), 100, &votes).unwrap();
+		let reversed_shares = NftModule::compute_shares(&test_order(14400), 100, &reversed).unwrap();
+		assert_eq!(shares, reversed_shares);
+		// 按质押区块长度降序, 再按账户升序
+		let owners: Vec<u64> = shares.iter().map(|(who, _)| *who).collect();
+		assert_eq!(owners, vec![2, 3, 4, 5]);
+	});
+}
+
+#[test]
+fn test_provenance() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		run_to_block(11);
+		assert_ok!(NftModule::transfer(Origin::signed(1), 2, 0));
+		run_to_block(12);
+		assert_ok!(NftModule::transfer(Origin::signed(2), 3, 0));
+		assert_ok!(NftModule::order_sell(Origin::signed(3), 0, 100, 200, 100, SellParams::default()));
+		run_to_block(13);
+		assert_ok!(NftModule::order_buy(Origin::signed(4), 0, 200, None));
+		assert_eq!(NftModule::provenance(0), vec![(1, 10), (2, 11), (3, 12), (4, 13)]);
+	});
+}
+
+#[test]
+fn test_safe_transfer_to_dead_account() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_eq!(Balances::free_balance(7), 0);
+		assert_noop!(NftModule::safe_transfer(Origin::signed(1), 7, 0), Error::<Test>::RecipientNotLive);
+		assert_ok!(NftModule::safe_transfer(Origin::signed(1), 2, 0));
+		assert_eq!(NftAccount::<Test>::get(&0), 2);
+		// 普通转移不检查接收人
+		assert_ok!(NftModule::transfer(Origin::signed(2), 7, 0));
+		assert_eq!(NftAccount::<Test>::get(&0), 7);
+	});
+}
+
+#[test]
+fn test_failed_auto_settlement_refunds_bid() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		// 卖家6在支付货币中没有账户, 成交时无法收款
+		assert_ok!(NftModule::create(Origin::signed(6), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::order_sell(Origin::signed(6), 0, 100, 200, 10, SellParams::default()));
+		assert_ok!(NftModule::order_buy(Origin::signed(2), 0, 150, None));
+
+		run_to_block(21);
+		assert!(FailedOrders::<Test>::contains_key(0));
+		assert!(Orders::<Test>::get(&0).is_some());
+		assert!(Bids::<Test>::get(&0).is_none());
+		assert_eq!(NftAccount::<Test>::get(&0), 6);
+		// 竞价者的资金没有损失
+		assert_eq!(PaymentBalances::reserved_balance(2), 0);
+		assert_eq!(PaymentBalances::free_balance(2), 11000);
+		let failed_event = TestEvent::nft_event(RawEvent::OrderFailed(0));
+		assert!(System::events().iter().any(|a| a.event == failed_event));
+
+		// 手动结算按流拍处理
+		assert_ok!(NftModule::order_settlement(Origin::signed(3), 0));
+		assert!(Orders::<Test>::get(&0).is_none());
+		assert!(!FailedOrders::<Test>::contains_key(0));
+		assert!(NftOrder::<Test>::get(&0).is_none());
+	});
+}
+
+#[test]
+fn test_failed_auto_settlement_falls_back_to_direct_payment() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		// 收款账户7在支付货币中没有账户, 正常成交时无法收款
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 200, 10, SellParams { payees: vec![(7, Permill::from_percent(100))], ..Default::default() }));
+		assert_ok!(NftModule::order_buy(Origin::signed(2), 0, 150, None));
+
+		// 兜底成交: 锁定的竞价资金扣除手续费后直接转给卖家
+		run_to_block(21);
+		assert!(!FailedOrders::<Test>::contains_key(0));
+		assert!(Orders::<Test>::get(&0).is_none());
+		assert_eq!(NftAccount::<Test>::get(&0), 2);
+		assert_eq!(PaymentBalances::reserved_balance(2), 0);
+		assert_eq!(PaymentBalances::free_balance(2), 11000 - 150);
+		assert_eq!(PaymentBalances::free_balance(1), 10000 + 143);
+		assert_eq!(PaymentBalances::free_balance(99), 1 + 7);
+		assert_eq!(TotalReserved::<Test>::get(), 0);
+	});
+}
+
+#[test]
+fn test_bid_queue_keeps_top_bids_reserved() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		set_max_queued_bids(2);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 1000, 10, SellParams::default()));
+		assert_ok!(NftModule::order_buy(Origin::signed(2), 0, 150, None));
+		assert_ok!(NftModule::order_buy(Origin::signed(3), 0, 200, None));
+		assert_ok!(NftModule::order_buy(Origin::signed(4), 0, 250, None));
+
+		assert_eq!(Bids::<Test>::get(&0).unwrap().owner, 4);
+		// 候补队列按价格从高到低排列, 资金保持锁定
+		let queue: Vec<(u64, u64)> = BidQueue::<Test>::get(&0).iter().map(|b| (b.owner, b.price)).collect();
+		assert_eq!(queue, vec![(3, 200), (2, 150)]);
+		assert_eq!(PaymentBalances::reserved_balance(2), 150);
+		assert_eq!(PaymentBalances::reserved_balance(3), 200);
+		assert_eq!(PaymentBalances::reserved_balance(4), 250);
+
+		// 队列已满时退还最低的候补出价
+		assert_ok!(NftModule::order_buy(Origin::signed(5), 0, 300, None));
+		let queue: Vec<(u64, u64)> = BidQueue::<Test>::get(&0).iter().map(|b| (b.owner, b.price)).collect();
+		assert_eq!(queue, vec![(4, 250), (3, 200)]);
+		assert_eq!(PaymentBalances::reserved_balance(2), 0);
+		assert_eq!(PaymentBalances::free_balance(2), 11000);
+
+		// 候补出价者重新成为最高出价者时, 之前的候补出价被退还
+		assert_ok!(NftModule::order_buy(Origin::signed(3), 0, 350, None));
+		let queue: Vec<(u64, u64)> = BidQueue::<Test>::get(&0).iter().map(|b| (b.owner, b.price)).collect();
+		assert_eq!(queue, vec![(5, 300), (4, 250)]);
+		assert_eq!(PaymentBalances::reserved_balance(3), 350);
+	});
+}
+
+#[test]
+fn test_bid_queue_fallback_winner_at_settlement() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		set_max_queued_bids(2);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 1000, 10, SellParams::default()));
+		assert_ok!(NftModule::order_buy(Origin::signed(3), 0, 200, None));
+		assert_ok!(NftModule::order_buy(Origin::signed(4), 0, 250, None));
+		assert_ok!(NftModule::order_buy(Origin::signed(5), 0, 300, None));
+		// 最高出价者的锁定资金被罚没, 无法成交
+		let _ = PaymentBalances::slash_reserved(&5, 300);
+
+		run_to_block(21);
+		// 由次高出价者成交
+		assert_eq!(NftAccount::<Test>::get(&0), 4);
+		assert!(Orders::<Test>::get(&0).is_none());
+		assert!(!FailedOrders::<Test>::contains_key(0));
+		assert_eq!(PaymentBalances::reserved_balance(4), 0);
+		assert_eq!(PaymentBalances::free_balance(4), 12750);
+		// 剩余的候补出价被退还
+		assert_eq!(PaymentBalances::reserved_balance(3), 0);
+		assert_eq!(PaymentBalances::free_balance(3), 12000);
+		assert!(BidQueue::<Test>::get(&0).is_empty());
+	});
+}
+
+#[test]
+fn test_nft_metadata_hash() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		let hash = [7u8; 32];
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), hash, 0));
+		assert_eq!(NftHash::<Test>::get(&0), hash);
+		let create_event = TestEvent::nft_event(RawEvent::NftCreated(1, 0, hash));
+		assert!(System::events().iter().any(|a| a.event == create_event));
+
+		// 只有所有者可以更新哈希
+		let new_hash = [9u8; 32];
+		assert_noop!(NftModule::update_hash(Origin::signed(2), 0, new_hash), Error::<Test>::NotNftOwner);
+		assert_ok!(NftModule::update_hash(Origin::signed(1), 0, new_hash));
+		assert_eq!(NftHash::<Test>::get(&0), new_hash);
+		let update_event = TestEvent::nft_event(RawEvent::NftHashUpdated(1, 0, new_hash));
+		assert!(System::events().iter().any(|a| a.event == update_event));
+
+		// 移除nft时清理哈希
+		assert_ok!(NftModule::remove(Origin::signed(1), 0));
+		assert!(!NftHash::<Test>::contains_key(&0));
+	});
+}
+
+#[test]
+fn test_buy_now_uses_effective_vote_lock() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 1000, 100, SellParams::default()));
+		assert_ok!(NftModule::vote_order(Origin::signed(2), 0, 200));
+		let order = Orders::<Test>::get(&0).unwrap();
+		let votes = Votes::<Test>::get(&0);
+		assert_eq!(votes[0].keep_block_num, 100);
+
+		// 在质押区块长度过半时一口价成交, 质押的有效锁定时长减半
+		run_to_block(60);
+		let effective = NftModule::effective_votes(&order, &votes);
+		assert_eq!(effective[0].keep_block_num, 50);
+		let shares = NftModule::compute_shares(&order, 1000, &effective).unwrap();
+		let expected: u64 = NftModule::share_payouts(1000, &shares).unwrap().iter().map(|(_, amount)| *amount).sum();
+
+		assert_ok!(NftModule::order_buy(Origin::signed(3), 0, 1000, None));
+		assert_eq!(NftAccount::<Test>::get(&0), 3);
+		assert_eq!(NftModule::nft_shares(0, 2), expected);
+	});
+}
+
+#[test]
+fn test_order_start_delay() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 1000, 20, SellParams { start_delay: Some(10), ..Default::default() }));
+		let order = Orders::<Test>::get(&0).unwrap();
+		// 到期区块随开始区块顺延
+		assert_eq!((order.create_block, order.start_block, order.expire_block), (10, 20, 40));
+
+		run_to_block(15);
+		assert_noop!(NftModule::order_buy(Origin::signed(2), 0, 150, None), Error::<Test>::OrderNotStarted);
+		assert_noop!(NftModule::vote_order(Origin::signed(3), 0, 100), Error::<Test>::OrderNotStarted);
+
+		run_to_block(20);
+		assert_ok!(NftModule::order_buy(Origin::signed(2), 0, 150, None));
+		assert_ok!(NftModule::vote_order(Origin::signed(3), 0, 100));
+		assert_eq!(Bids::<Test>::get(&0).unwrap().owner, 2);
+	});
+}
+
+#[test]
+fn test_transfers_blocked() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::set_transfers_blocked(Origin::signed(2), true));
+		let blocked_event = TestEvent::nft_event(RawEvent::TransfersBlockedChanged(2, true));
+		assert!(System::events().iter().any(|a| a.event == blocked_event));
+
+		// 拒绝接收直接转移
+		assert_noop!(NftModule::transfer(Origin::signed(1), 2, 0), Error::<Test>::RecipientBlocksTransfers);
+		assert_noop!(NftModule::safe_transfer(Origin::signed(1), 2, 0), Error::<Test>::RecipientBlocksTransfers);
+
+		// 仍然可以通过拍卖购买
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 200, 10, SellParams::default()));
+		assert_ok!(NftModule::order_buy(Origin::signed(2), 0, 200, None));
+		assert_eq!(NftAccount::<Test>::get(&0), 2);
+
+		// 取消拒绝后可以接收转移
+		assert_ok!(NftModule::set_transfers_blocked(Origin::signed(2), false));
+		assert_ok!(NftModule::transfer(Origin::signed(2), 3, 0));
+		assert_ok!(NftModule::transfer(Origin::signed(3), 2, 0));
+		assert_eq!(NftAccount::<Test>::get(&0), 2);
+	});
+}
+
+#[test]
+fn test_order_settlement_early_return_weight() {
+	use frame_support::weights::GetDispatchInfo;
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 200, 10, SellParams::default()));
+
+		let declared = Call::<Test>::order_settlement(0).get_dispatch_info().weight;
+		let err = NftModule::order_settlement(Origin::signed(2), 0).unwrap_err();
+		assert_eq!(err.error, Error::<Test>::IsNotTimeToSettlement.into());
+		assert!(err.post_info.actual_weight.unwrap() < declared);
+
+		let err = NftModule::order_settlement(Origin::signed(2), 1).unwrap_err();
+		assert_eq!(err.error, Error::<Test>::OrderNotExist.into());
+		assert!(err.post_info.actual_weight.unwrap() < declared);
+
+		// 正常结算按声明的权重收费, 不经过on_initialize直接手动结算
+		System::set_block_number(21);
+		let post_info = NftModule::order_settlement(Origin::signed(2), 0).unwrap();
+		assert_eq!(post_info.actual_weight, None);
+	});
+}
+
+#[test]
+fn test_delegate_buy() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 1000, 10, SellParams::default()));
+
+		// 未设置代理时不能代为竞拍
+		assert_noop!(NftModule::delegate_buy(Origin::signed(3), 2, 0, 150, None), Error::<Test>::NotBidDelegate);
+		assert_ok!(NftModule::set_bid_delegate(Origin::signed(2), Some(3)));
+		let delegate_event = TestEvent::nft_event(RawEvent::BidDelegateChanged(2, Some(3)));
+		assert!(System::events().iter().any(|a| a.event == delegate_event));
+
+		// 代理账户竞拍时锁定委托人的资金, 竞价属于委托人
+		assert_ok!(NftModule::delegate_buy(Origin::signed(3), 2, 0, 150, None));
+		assert_eq!(Bids::<Test>::get(&0).unwrap().owner, 2);
+		assert_eq!(PaymentBalances::reserved_balance(2), 150);
+		assert_eq!(PaymentBalances::reserved_balance(3), 0);
+
+		// 结算后nft归委托人
+		run_to_block(21);
+		assert_eq!(NftAccount::<Test>::get(&0), 2);
+		assert_eq!(PaymentBalances::free_balance(2), 11000 - 150);
+		assert_eq!(PaymentBalances::free_balance(3), 12000);
+
+		// 取消代理后不能再代为竞拍
+		assert_ok!(NftModule::set_bid_delegate(Origin::signed(2), None));
+		assert!(BidDelegates::<Test>::get(&2).is_none());
+	});
+}
+
+#[test]
+fn test_listing_deposit() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		set_listing_deposit(50);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 200, 10, SellParams::default()));
+		assert_eq!(Orders::<Test>::get(&0).unwrap().deposit, 50);
+		assert_eq!(PaymentBalances::reserved_balance(1), 50);
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 1, 100, 200, 10, SellParams::default()));
+		assert_eq!(PaymentBalances::reserved_balance(1), 100);
+
+		// 成交时退还押金
+		assert_ok!(NftModule::order_buy(Origin::signed(2), 0, 200, None));
+		assert_eq!(NftAccount::<Test>::get(&0), 2);
+		assert_eq!(PaymentBalances::reserved_balance(1), 50);
+
+		// 流拍时退还押金
+		run_to_block(21);
+		assert!(Orders::<Test>::get(&1).is_none());
+		assert_eq!(PaymentBalances::reserved_balance(1), 0);
+		assert_eq!(NftModule::total_value_locked(), 0);
+	});
+}
+
+#[test]
+fn test_voter_count() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 1000, 100, SellParams::default()));
+		assert_ok!(NftModule::vote_order(Origin::signed(2), 0, 100));
+		assert_ok!(NftModule::vote_order(Origin::signed(3), 0, 100));
+		assert_ok!(NftModule::vote_order(Origin::signed(4), 0, 100));
+		// 同一账户重复质押不重复计数
+		assert_ok!(NftModule::vote_order(Origin::signed(4), 0, 100));
+		assert_eq!(NftModule::voter_count(0), 3);
+
+		// 质押减少到0时移除
+		assert_ok!(NftModule::reduce_vote(Origin::signed(3), 0, 100));
+		assert_eq!(NftModule::voter_count(0), 2);
+
+		run_to_block(111);
+		assert_eq!(NftModule::voter_count(0), 0);
+	});
+}
+
+#[test]
+fn test_paused() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 1000, 10, SellParams::default()));
+
+		assert_noop!(NftModule::set_paused(Origin::signed(1), true), DispatchError::BadOrigin);
+		assert_ok!(NftModule::set_paused(Origin::root(), true));
+		let paused_event = TestEvent::nft_event(RawEvent::PausedChanged(true));
+		assert!(System::events().iter().any(|a| a.event == paused_event));
+
+		assert_noop!(NftModule::order_sell(Origin::signed(1), 1, 100, 1000, 10, SellParams::default()), Error::<Test>::Paused);
+		assert_noop!(NftModule::order_buy(Origin::signed(2), 0, 150, None), Error::<Test>::Paused);
+		assert_noop!(NftModule::vote_order(Origin::signed(3), 0, 100), Error::<Test>::Paused);
+		assert_noop!(NftModule::order_settlement(Origin::signed(2), 0), Error::<Test>::Paused.with_weight(0));
+		assert_noop!(NftModule::cancel_order(Origin::signed(1), 0), Error::<Test>::Paused);
+		assert_noop!(NftModule::update_order_price(Origin::signed(1), 0, 200, 1000), Error::<Test>::Paused);
+		assert_noop!(NftModule::extend_order(Origin::signed(1), 0, 5), Error::<Test>::Paused);
+		assert_noop!(NftModule::confirm_buyout(Origin::signed(2), 0), Error::<Test>::Paused);
+		assert_noop!(NftModule::cancel_buyout(Origin::signed(2), 0), Error::<Test>::Paused);
+		assert_noop!(NftModule::claim_dividend(Origin::signed(1), 0), Error::<Test>::Paused);
+		// 空闲nft的转移不受影响
+		assert_ok!(NftModule::transfer(Origin::signed(1), 2, 1));
+
+		// 解除暂停后恢复交易
+		assert_ok!(NftModule::set_paused(Origin::root(), false));
+		assert_ok!(NftModule::order_buy(Origin::signed(2), 0, 150, None));
+		assert_ok!(NftModule::vote_order(Origin::signed(3), 0, 100));
+		assert_ok!(NftModule::order_sell(Origin::signed(2), 1, 100, 1000, 10, SellParams::default()));
+	});
+}
+
+#[test]
+fn test_paused_skips_auto_settlement() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 1000, 10, SellParams::default()));
+		assert_ok!(NftModule::order_buy(Origin::signed(2), 0, 150, None));
+		assert_ok!(NftModule::set_paused(Origin::root(), true));
+
+		// 暂停期间到期的订单不会自动结算, 顺延到下一个区块
+		run_to_block(25);
+		assert!(Orders::<Test>::get(&0).is_some());
+		assert_eq!(NftAccount::<Test>::get(&0), 1);
+		assert_eq!(ExpiringOrders::<Test>::get(26), vec![0]);
+
+		// 解除暂停后自动结算
+		assert_ok!(NftModule::set_paused(Origin::root(), false));
+		run_to_block(26);
+		assert!(Orders::<Test>::get(&0).is_none());
+		assert_eq!(NftAccount::<Test>::get(&0), 2);
+	});
+}
+
+#[test]
+fn test_order_fee_rate_override() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::create(Origin::signed(2), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		// 非管理员不能设置低于默认比例的手续费, 但可以设置更高的比例
+		assert_noop!(
+			NftModule::order_sell(Origin::signed(2), 2, 100, 1000, 10, SellParams { fee_rate: Some(Permill::zero()), ..Default::default() }),
+			Error::<Test>::FeeRateNotAllowed
+		);
+		assert_ok!(NftModule::order_sell(Origin::signed(2), 2, 100, 1000, 10, SellParams { fee_rate: Some(Permill::from_percent(10)), ..Default::default() }));
+
+		// 管理员账户1挂出默认手续费订单和免手续费订单
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 1000, 10, SellParams::default()));
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 1, 100, 1000, 10, SellParams { fee_rate: Some(Permill::zero()), ..Default::default() }));
+
+		let balance = PaymentBalances::free_balance(1);
+		assert_ok!(NftModule::order_buy(Origin::signed(3), 0, 1000, None));
+		let default_proceeds = PaymentBalances::free_balance(1) - balance;
+		let balance = PaymentBalances::free_balance(1);
+		assert_ok!(NftModule::order_buy(Origin::signed(4), 1, 1000, None));
+		let promo_proceeds = PaymentBalances::free_balance(1) - balance;
+		// 默认扣除5%手续费, 免手续费订单卖家获得全部成交价
+		assert_eq!(default_proceeds, 950);
+		assert_eq!(promo_proceeds, 1000);
+	});
+}
+
+#[test]
+fn test_fee_rate_override_cannot_exceed_price() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		// 结算奖励1% + 分红10% + 分润20%, 手续费最多69%
+		assert_noop!(
+			NftModule::order_sell(Origin::signed(1), 0, 100, 1000, 10, SellParams { fee_rate: Some(Permill::from_percent(70)), ..Default::default() }),
+			Error::<Test>::FeeRateTooHigh
+		);
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 1000, 10, SellParams { fee_rate: Some(Permill::from_percent(69)), ..Default::default() }));
+	});
+}
+
+#[test]
+fn test_configured_rates_pass_integrity_test() {
+	new_test_ext().execute_with(|| {
+		<NftModule as frame_support::traits::IntegrityTest>::integrity_test();
+	});
+}
+
+#[test]
+fn test_bundle_sell() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		for _ in 0..3 {
+			assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		}
+		assert_noop!(NftModule::bundle_sell(Origin::signed(1), vec![0], 100, 1000, 10), Error::<Test>::BundleTooSmall);
+		assert_noop!(NftModule::bundle_sell(Origin::signed(1), vec![0, 0], 100, 1000, 10), Error::<Test>::BundleDuplicateNft);
+		assert_ok!(NftModule::bundle_sell(Origin::signed(1), vec![0, 1], 100, 1000, 10));
+		assert_eq!(BundleOrders::<Test>::get(&0), vec![0, 1]);
+		assert_eq!(Orders::<Test>::get(&0).unwrap().nft_id, 0);
+		assert_eq!(NftOrder::<Test>::get(&1), Some(0));
+
+		// 包中的nft不能移除、转移或单独出售
+		assert_noop!(NftModule::remove(Origin::signed(1), 1), Error::<Test>::NftOrderExist);
+		assert_noop!(NftModule::transfer(Origin::signed(1), 2, 1), Error::<Test>::NftOrderExist);
+		assert_noop!(NftModule::order_sell(Origin::signed(1), 1, 100, 1000, 10, SellParams::default()), Error::<Test>::NftOrderExist);
+		// 已在订单中的nft不能再打包
+		assert_noop!(NftModule::bundle_sell(Origin::signed(1), vec![2, 1], 100, 1000, 10), Error::<Test>::NftOrderExist);
+
+		// 成交后所有nft转移给购买者
+		assert_ok!(NftModule::order_buy(Origin::signed(2), 0, 150, None));
+		run_to_block(21);
+		assert_eq!(NftAccount::<Test>::get(&0), 2);
+		assert_eq!(NftAccount::<Test>::get(&1), 2);
+		assert_eq!(NftAccount::<Test>::get(&2), 1);
+		assert!(NftOrder::<Test>::get(&1).is_none());
+		assert!(BundleOrders::<Test>::get(&0).is_empty());
+		assert_ok!(NftModule::transfer(Origin::signed(2), 3, 1));
+	});
+}
+
+#[test]
+fn test_vote_refunded_events() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 1000, 100, SellParams::default()));
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 1, 100, 1000, 100, SellParams::default()));
+		assert_ok!(NftModule::vote_order(Origin::signed(2), 0, 200));
+		assert_ok!(NftModule::vote_order(Origin::signed(3), 0, 300));
+		assert_ok!(NftModule::vote_order(Origin::signed(4), 1, 400));
+		// 订单0成交, 订单1流拍
+		assert_ok!(NftModule::order_buy(Origin::signed(5), 0, 500, None));
+
+		run_to_block(111);
+		for (who, order_id, amount) in vec![(2, 0, 200), (3, 0, 300), (4, 1, 400)] {
+			let refund_event = TestEvent::nft_event(RawEvent::VoteRefunded(who, order_id, amount));
+			assert!(System::events().iter().any(|a| a.event == refund_event));
+		}
+	});
+}
+
+#[test]
+fn test_vote_order_at_expiry_boundary() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 1000, 100, SellParams::default()));
+		let expire_block = Orders::<Test>::get(&0).unwrap().expire_block;
+
+		// 到期区块剩余锁定时长为0, 按临近到期处理
+		System::set_block_number(expire_block);
+		assert_noop!(NftModule::vote_order(Origin::signed(2), 0, 100), Error::<Test>::VoteWindowClosed);
+		// 超过到期区块时返回拍卖已结束, 而不是区块号溢出
+		System::set_block_number(expire_block + 1);
+		assert_noop!(NftModule::vote_order(Origin::signed(2), 0, 100), Error::<Test>::IsTimeToSettlement);
+	});
+}
+
+#[test]
+fn test_approval_for_all() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		for _ in 0..4 {
+			assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		}
+		assert_noop!(NftModule::transfer(Origin::signed(3), 4, 0), Error::<Test>::NotNftOwner);
+		assert_ok!(NftModule::set_approval_for_all(Origin::signed(1), 3, true));
+		let approval_event = TestEvent::nft_event(RawEvent::ApprovalForAll(1, 3, true));
+		assert!(System::events().iter().any(|a| a.event == approval_event));
+
+		// 操作员可以转移所有者的多个nft
+		assert_ok!(NftModule::transfer(Origin::signed(3), 4, 0));
+		assert_ok!(NftModule::transfer(Origin::signed(3), 5, 1));
+		assert_eq!(NftAccount::<Test>::get(&0), 4);
+		assert_eq!(NftAccount::<Test>::get(&1), 5);
+		let transfer_event = TestEvent::nft_event(RawEvent::NftTransfer(1, 4, 0));
+		assert!(System::events().iter().any(|a| a.event == transfer_event));
+		// 操作员不能转移已经不属于所有者的nft
+		assert_noop!(NftModule::transfer(Origin::signed(3), 2, 0), Error::<Test>::NotNftOwner);
+
+		// 操作员挂单时订单属于nft所有者
+		assert_ok!(NftModule::order_sell(Origin::signed(3), 2, 100, 1000, 10, SellParams::default()));
+		assert_eq!(Orders::<Test>::get(&0).unwrap().owner, 1);
+		assert_eq!(OrderCountByOwner::<Test>::get(&1), 1);
+
+		// 取消授权后不能再操作
+		assert_ok!(NftModule::set_approval_for_all(Origin::signed(1), 3, false));
+		assert_noop!(NftModule::transfer(Origin::signed(3), 4, 3), Error::<Test>::NotNftOwner);
+		assert_noop!(NftModule::order_sell(Origin::signed(3), 3, 100, 1000, 10, SellParams::default()), Error::<Test>::NotNftOwner);
+	});
+}
+
+#[test]
+fn test_price_tick() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		set_price_tick(10);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_noop!(NftModule::order_sell(Origin::signed(1), 0, 105, 1000, 10, SellParams::default()), Error::<Test>::PriceNotOnTick);
+		assert_noop!(NftModule::order_sell(Origin::signed(1), 0, 100, 1001, 10, SellParams::default()), Error::<Test>::PriceNotOnTick);
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 1000, 10, SellParams::default()));
+
+		assert_noop!(NftModule::order_buy(Origin::signed(2), 0, 155, None), Error::<Test>::PriceNotOnTick);
+		assert_ok!(NftModule::order_buy(Origin::signed(2), 0, 150, None));
+		assert_noop!(NftModule::order_buy(Origin::signed(3), 0, 161, None), Error::<Test>::PriceNotOnTick);
+		assert_ok!(NftModule::order_buy(Origin::signed(3), 0, 160, None));
+		assert_eq!(Bids::<Test>::get(&0).unwrap().price, 160);
+	});
+}
+
+#[test]
+fn test_blocks_until_settlement() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_eq!(NftModule::blocks_until_settlement(0), None);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 1000, 10, SellParams::default()));
+		// 到期区块20, 第21个区块可以结算
+		assert_eq!(NftModule::blocks_until_settlement(0), Some(11));
+		run_to_block(15);
+		assert_eq!(NftModule::blocks_until_settlement(0), Some(6));
+		run_to_block(20);
+		assert_eq!(NftModule::blocks_until_settlement(0), Some(1));
+		// 不经过on_initialize, 订单保留到可以结算的区块
+		System::set_block_number(21);
+		assert_eq!(NftModule::blocks_until_settlement(0), Some(0));
+		System::set_block_number(30);
+		assert_eq!(NftModule::blocks_until_settlement(0), Some(0));
+	});
+}
+
+#[test]
+fn test_slashed_listing_deposit_goes_to_treasury() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		set_listing_deposit(50);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 1000, 10, SellParams::default()));
+		assert_ok!(NftModule::order_buy(Origin::signed(2), 0, 150, None));
+		// 挂单的nft被移除, 卖家无法交付, 自动结算失败
+		Nfts::<Test>::remove(0);
+		assert_eq!(PaymentBalances::free_balance(TreasuryAccount::get()), 0);
+
+		run_to_block(21);
+		assert!(FailedOrders::<Test>::contains_key(0));
+		// 挂单押金罚没给国库账户
+		assert_eq!(PaymentBalances::reserved_balance(1), 0);
+		assert_eq!(PaymentBalances::free_balance(1), 10000 - 50);
+		assert_eq!(PaymentBalances::free_balance(TreasuryAccount::get()), 50);
+		assert_eq!(Orders::<Test>::get(&0).unwrap().deposit, 0);
+	});
+}
+
+#[test]
+fn test_buyer_side_settlement_failure_refunds_listing_deposit() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		set_listing_deposit(50);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 1000, 10, SellParams::default()));
+		assert_ok!(NftModule::order_buy(Origin::signed(2), 0, 150, None));
+		// 竞价者的锁定资金被罚没, 自动结算失败, 不是卖家的责任
+		let _ = PaymentBalances::slash_reserved(&2, 150);
+
+		run_to_block(21);
+		assert!(FailedOrders::<Test>::contains_key(0));
+		// 挂单押金退还给卖家, 国库账户没有收入
+		assert_eq!(PaymentBalances::reserved_balance(1), 0);
+		assert_eq!(PaymentBalances::free_balance(1), 10000);
+		assert_eq!(PaymentBalances::free_balance(TreasuryAccount::get()), 0);
+		assert_eq!(Orders::<Test>::get(&0).unwrap().deposit, 0);
+	});
+}
+
+#[test]
+fn test_order_buy_below_start_and_current_bid() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 1000, 10, SellParams::default()));
+		assert_noop!(NftModule::order_buy(Origin::signed(2), 0, 99, None), Error::<Test>::BelowStartPrice);
+		assert_ok!(NftModule::order_buy(Origin::signed(2), 0, 150, None));
+		assert_noop!(NftModule::order_buy(Origin::signed(3), 0, 150, None), Error::<Test>::BelowCurrentBid);
+		assert_noop!(NftModule::order_buy(Origin::signed(3), 0, 120, None), Error::<Test>::BelowCurrentBid);
+	});
+}
+
+#[test]
+fn test_split_price_sums_to_total() {
+	new_test_ext().execute_with(|| {
+		let prices: Vec<u64> = vec![0, 1, 2, 3, 7, 99, 100, 101, 999, 1000, 12345, 987_654_321, u64::max_value()];
+		let rates = vec![
+			Permill::zero(),
+			Permill::from_parts(1),
+			Permill::from_percent(1),
+			Permill::from_parts(333_333),
+			Permill::from_percent(50),
+			Permill::from_parts(999_999),
+			Permill::one(),
+		];
+		for price in prices.iter() {
+			for fee_rate in rates.iter() {
+				for reward_rate in rates.iter() {
+					let (fee, reward, proceeds) = NftModule::split_price(*price, *fee_rate, *reward_rate);
+					// 三部分之和等于成交价, 手续费和奖励向下取整
+					assert_eq!(fee as u128 + reward as u128 + proceeds as u128, *price as u128);
+					assert!(fee <= fee_rate.mul_ceil(*price));
+					assert!(reward <= reward_rate.mul_floor(*price));
+				}
+			}
+		}
+	});
+}
+
+#[test]
+fn test_order_complete_conserves_total_issuance() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		let issuance = PaymentBalances::total_issuance();
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 1000, 10, SellParams::default()));
+		assert_ok!(NftModule::order_buy(Origin::signed(2), 0, 333, None));
+		System::set_block_number(21);
+		let balances_before: u64 = vec![1, 2, 5, FeeAccount::get()].iter().map(|who| PaymentBalances::total_balance(who)).sum();
+		assert_ok!(NftModule::order_settlement(Origin::signed(5), 0));
+		let balances_after: u64 = vec![1, 2, 5, FeeAccount::get()].iter().map(|who| PaymentBalances::total_balance(who)).sum();
+		assert_eq!(balances_before, balances_after);
+		assert_eq!(PaymentBalances::total_issuance(), issuance);
+	});
+}
+
+#[test]
+fn test_reclaim_nft() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_noop!(NftModule::reclaim_nft(Origin::signed(2), 0, 3), DispatchError::BadOrigin);
+		assert_noop!(NftModule::reclaim_nft(Origin::root(), 0, 3), Error::<Test>::OwnerStillActive);
+
+		// 所有者转出全部余额, 账户被回收
+		assert_ok!(Balances::transfer(Origin::signed(1), 2, 1000));
+		assert_eq!(Balances::total_balance(&1), 0);
+
+		assert_ok!(NftModule::reclaim_nft(Origin::root(), 0, 3));
+		assert_eq!(NftAccount::<Test>::get(&0), 3);
+		assert_eq!(OwnedNfts::<Test>::get(&3), vec![0]);
+		let transfer_event = TestEvent::nft_event(RawEvent::NftTransfer(1, 3, 0));
+		assert!(System::events().iter().any(|a| a.event == transfer_event));
+	});
+}
+
+#[test]
+fn test_vote_weight() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_eq!(NftModule::vote_weight(0, 1000, 7200), 0);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		// 拍卖2天
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 1000, 28800, SellParams::default()));
+
+		// 质押半天: 1000 * 0.5 / 2
+		let internal = NftModule::vote_pre_weight(U64F64::from_num(1000), U64F64::from_num(0.5), U64F64::from_num(2)).unwrap();
+		assert_eq!(internal, U64F64::from_num(250));
+		assert_eq!(NftModule::vote_weight(0, 1000, 7200), 250);
+		// 质押整个拍卖时长时权重等于质押数量
+		assert_eq!(NftModule::vote_weight(0, 1000, 28800), 1000);
+		assert_eq!(NftModule::vote_weight(0, 1000, 0), 0);
+	});
+}
+
+#[test]
+fn test_second_price_settlement() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		// 第二价格结算只适用于英式拍卖
+		assert_noop!(
+			NftModule::order_sell(Origin::signed(1), 2, 100, 1000, 10, SellParams { kind: AuctionKind::Dutch, settlement_mode: SettlementMode::SecondPrice, ..Default::default() }),
+			Error::<Test>::SecondPriceNotEnglish
+		);
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 1000, 10, SellParams::default()));
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 1, 100, 1000, 10, SellParams { settlement_mode: SettlementMode::SecondPrice, ..Default::default() }));
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 2, 100, 1000, 10, SellParams { settlement_mode: SettlementMode::SecondPrice, ..Default::default() }));
+		// 两个订单的出价相同
+		assert_ok!(NftModule::order_buy(Origin::signed(2), 0, 150, None));
+		assert_ok!(NftModule::order_buy(Origin::signed(3), 0, 300, None));
+		assert_ok!(NftModule::order_buy(Origin::signed(5), 1, 150, None));
+		assert_ok!(NftModule::order_buy(Origin::signed(4), 1, 300, None));
+		// 没有候补队列配置时, 第二价格订单仍然保留次高出价
+		assert_eq!(PaymentBalances::reserved_balance(2), 0);
+		assert_eq!(PaymentBalances::reserved_balance(5), 150);
+		// 只有一个出价的第二价格订单
+		assert_ok!(NftModule::order_buy(Origin::signed(2), 2, 200, None));
+
+		run_to_block(21);
+		// 第一价格: 成交者支付自己的出价300
+		assert_eq!(NftAccount::<Test>::get(&0), 3);
+		assert_eq!(PaymentBalances::free_balance(3), 12000 - 300);
+		// 第二价格: 成交者支付次高出价150, 差额退还
+		assert_eq!(NftAccount::<Test>::get(&1), 4);
+		assert_eq!(PaymentBalances::free_balance(4), 13000 - 150);
+		assert_eq!(PaymentBalances::reserved_balance(4), 0);
+		assert_eq!(PaymentBalances::free_balance(5), 14000);
+		// 没有次高出价时支付起拍价格
+		assert_eq!(NftAccount::<Test>::get(&2), 2);
+		assert_eq!(PaymentBalances::free_balance(2), 11000 - 100);
+		assert_eq!(PaymentBalances::reserved_balance(2), 0);
+		// 卖家收入: (300 - 15) + (150 - 7) + (100 - 5)
+		assert_eq!(PaymentBalances::free_balance(1), 10000 + 285 + 143 + 95);
+		assert_eq!(PaymentBalances::free_balance(99), 1 + 15 + 7 + 5);
+	});
+}
+
+#[test]
+fn test_unique_urls() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		// 未开启时允许重复的url
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+
+		set_unique_urls(true);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "unique_url".into(), "desc_value".into(), [0u8; 32], 0));
+		assert!(UrlSeen::get(b"unique_url".to_vec()));
+		assert_noop!(
+			NftModule::create(Origin::signed(2), "title_value".into(), "unique_url".into(), "desc_value".into(), [0u8; 32], 0),
+			Error::<Test>::DuplicateUrl
+		);
+
+		// nft移除后url可以重新使用
+		assert_ok!(NftModule::remove(Origin::signed(1), 2));
+		assert!(!UrlSeen::get(b"unique_url".to_vec()));
+		assert_ok!(NftModule::create(Origin::signed(2), "title_value".into(), "unique_url".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_eq!(NftAccount::<Test>::get(&3), 2);
+	});
+}
+
+#[test]
+fn test_id_counter_near_exhaustion() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		let warned = |counter: IdCounter, remaining: u128| {
+			let event = TestEvent::nft_event(RawEvent::IdCounterNearExhaustion(counter, remaining));
+			System::events().iter().any(|a| a.event == event)
+		};
+		// 剩余Id数不低于阈值时不预警
+		NextNftId::<Test>::put(u32::max_value() - 11);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert!(!warned(IdCounter::Nft, 10));
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert!(warned(IdCounter::Nft, 9));
+
+		// 在溢出之前预警
+		NextNftId::<Test>::put(u32::max_value() - 1);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert!(warned(IdCounter::Nft, 0));
+		assert_noop!(
+			NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0),
+			Error::<Test>::NftIdOverflow
+		);
+
+		NextOrderId::<Test>::put(u32::max_value() - 5);
+		assert_ok!(NftModule::order_sell(Origin::signed(1), u32::max_value() - 11, 100, 1000, 10, SellParams::default()));
+		assert!(warned(IdCounter::Order, 4));
+	});
+}
+
+#[test]
+fn test_buyout_cooldown_confirm() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		set_buyout_cooldown(5);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 1000, 10, SellParams::default()));
+		assert_ok!(NftModule::order_buy(Origin::signed(2), 0, 1000, None));
+
+		// 冷静期内资金锁定, nft不转移, 订单不能继续竞价
+		assert_eq!(PendingBuyouts::<Test>::get(&0), Some((2, 1000, 15)));
+		assert_eq!(PaymentBalances::reserved_balance(2), 1000);
+		assert_eq!(NftAccount::<Test>::get(&0), 1);
+		let pending_event = TestEvent::nft_event(RawEvent::BuyoutPending(2, 0, 15));
+		assert!(System::events().iter().any(|a| a.event == pending_event));
+		assert_noop!(NftModule::order_buy(Origin::signed(3), 0, 1000, None), Error::<Test>::BuyoutPending);
+		// 冷静期内卖家不能改价或延期
+		assert_noop!(NftModule::update_order_price(Origin::signed(1), 0, 200, 2000), Error::<Test>::BuyoutPending);
+		assert_noop!(NftModule::extend_order(Origin::signed(1), 0, 5), Error::<Test>::BuyoutPending);
+
+		// 只有买家可以确认
+		assert_noop!(NftModule::confirm_buyout(Origin::signed(3), 0), Error::<Test>::NotBuyoutBuyer);
+		assert_ok!(NftModule::confirm_buyout(Origin::signed(2), 0));
+		assert_eq!(NftAccount::<Test>::get(&0), 2);
+		assert!(Orders::<Test>::get(&0).is_none());
+		assert!(PendingBuyouts::<Test>::get(&0).is_none());
+		assert_eq!(PaymentBalances::free_balance(2), 11000 - 1000);
+		assert_eq!(PaymentBalances::free_balance(1), 10000 + 950);
+		assert_noop!(NftModule::confirm_buyout(Origin::signed(2), 0), Error::<Test>::NoPendingBuyout);
+	});
+}
+
+#[test]
+fn test_buyout_cooldown_auto_finalize() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		set_buyout_cooldown(5);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 1000, 10, SellParams { kind: AuctionKind::FixedPrice, ..Default::default() }));
+		assert_ok!(NftModule::order_buy(Origin::signed(2), 0, 100, None));
+
+		run_to_block(14);
+		assert_eq!(NftAccount::<Test>::get(&0), 1);
+		// 冷静期结束后自动成交
+		run_to_block(15);
+		assert_eq!(NftAccount::<Test>::get(&0), 2);
+		assert!(Orders::<Test>::get(&0).is_none());
+		assert!(PendingBuyouts::<Test>::get(&0).is_none());
+		assert_eq!(PaymentBalances::reserved_balance(2), 0);
+		assert_eq!(PaymentBalances::free_balance(2), 11000 - 100);
+	});
+}
+
+#[test]
+fn test_buyout_cooldown_cancel_with_penalty() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		set_buyout_cooldown(5);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 1000, 10, SellParams::default()));
+		assert_ok!(NftModule::order_buy(Origin::signed(2), 0, 1000, None));
+
+		// 取消时支付10%违约金给卖家, 其余退还
+		assert_noop!(NftModule::cancel_buyout(Origin::signed(3), 0), Error::<Test>::NotBuyoutBuyer);
+		assert_ok!(NftModule::cancel_buyout(Origin::signed(2), 0));
+		let cancel_event = TestEvent::nft_event(RawEvent::BuyoutCancelled(2, 0, 100));
+		assert!(System::events().iter().any(|a| a.event == cancel_event));
+		assert_eq!(PaymentBalances::reserved_balance(2), 0);
+		assert_eq!(PaymentBalances::free_balance(2), 11000 - 100);
+		assert_eq!(PaymentBalances::free_balance(1), 10000 + 100);
+		assert!(PendingBuyouts::<Test>::get(&0).is_none());
+
+		// 订单恢复拍卖, 冷静期跨过到期区块时暂不结算
+		run_to_block(18);
+		assert_ok!(NftModule::order_buy(Origin::signed(3), 0, 1000, None));
+		run_to_block(21);
+		assert!(Orders::<Test>::get(&0).is_some());
+		assert_noop!(NftModule::order_settlement(Origin::signed(4), 0), Error::<Test>::BuyoutPending);
+
+		// 取消已到期订单的购买后, 订单在下一个区块按流拍结算
+		assert_ok!(NftModule::cancel_buyout(Origin::signed(3), 0));
+		run_to_block(22);
+		assert!(Orders::<Test>::get(&0).is_none());
+		assert_eq!(NftAccount::<Test>::get(&0), 1);
+		assert_eq!(PaymentBalances::free_balance(3), 12000 - 100);
+		assert_eq!(PaymentBalances::free_balance(1), 10000 + 200);
+	});
+}
+
+#[test]
+fn test_order_sell_zero_keep_block_num() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		// 最小保留区块数配置为0时仍然拒绝0区块的拍卖
+		set_min_keep_block_number(0);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_noop!(
+			NftModule::order_sell(Origin::signed(1), 0, 100, 1000, 0, SellParams::default()),
+			Error::<Test>::KeepBlockNumTooSmall
+		);
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 1000, 1, SellParams::default()));
+	});
+}
+
+#[test]
+fn test_nft_sales_stats() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_eq!(NftModule::sales_stats(0), (0, 0, 0));
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 1000, 10, SellParams { kind: AuctionKind::FixedPrice, ..Default::default() }));
+		assert_ok!(NftModule::order_buy(Origin::signed(2), 0, 100, None));
+		assert_eq!(NftModule::sales_stats(0), (1, 100, 100));
+
+		// 新所有者再次出售, 统计继续累计
+		assert_ok!(NftModule::order_sell(Origin::signed(2), 0, 300, 1000, 10, SellParams { kind: AuctionKind::FixedPrice, ..Default::default() }));
+		assert_ok!(NftModule::order_buy(Origin::signed(3), 1, 300, None));
+		assert_eq!(NftAccount::<Test>::get(&0), 3);
+		assert_eq!(NftModule::sales_stats(0), (2, 400, 300));
+	});
+}
+
+#[test]
+fn test_max_reserved_per_account() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		set_max_reserved_per_account(500);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 1000, 100, SellParams::default()));
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 1, 100, 1000, 100, SellParams::default()));
+
+		// 出价和质押合计接近上限
+		assert_ok!(NftModule::order_buy(Origin::signed(2), 0, 300, None));
+		assert_ok!(NftModule::vote_order(Origin::signed(2), 1, 150));
+		assert_eq!(ReservedByAccount::<Test>::get(&2), 450);
+		assert_noop!(NftModule::vote_order(Origin::signed(2), 1, 100), Error::<Test>::ReservedLimitExceeded);
+		assert_noop!(NftModule::order_buy(Origin::signed(2), 1, 100, None), Error::<Test>::ReservedLimitExceeded);
+		assert_ok!(NftModule::vote_order(Origin::signed(2), 1, 50));
+		assert_eq!(ReservedByAccount::<Test>::get(&2), 500);
+
+		// 出价被超过并退还后可以继续出价
+		assert_ok!(NftModule::order_buy(Origin::signed(3), 0, 400, None));
+		assert_eq!(ReservedByAccount::<Test>::get(&2), 200);
+		assert_ok!(NftModule::order_buy(Origin::signed(2), 1, 300, None));
+		assert_eq!(ReservedByAccount::<Test>::get(&2), 500);
+		assert_eq!(PaymentBalances::reserved_balance(2), 500);
+	});
+}
+
+#[test]
+fn test_order_payees_split() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		// 比例之和必须为100%
+		assert_noop!(
+			NftModule::order_sell(Origin::signed(1), 0, 1000, 1000, 10, SellParams {
+				kind: AuctionKind::FixedPrice,
+				payees: vec![(4, Permill::from_percent(60)), (5, Permill::from_percent(30))],
+				..Default::default()
+			}),
+			Error::<Test>::PayeeSplitInvalid
+		);
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 1000, 1000, 10, SellParams {
+			kind: AuctionKind::FixedPrice,
+			payees: vec![(4, Permill::from_percent(60)), (5, Permill::from_percent(40))],
+			..Default::default()
+		}));
+		assert_ok!(NftModule::order_buy(Origin::signed(2), 0, 1000, None));
+
+		// 扣除5%手续费后的950按60/40分给收款账户
+		assert_eq!(NftAccount::<Test>::get(&0), 2);
+		assert_eq!(PaymentBalances::free_balance(4), 13000 + 570);
+		assert_eq!(PaymentBalances::free_balance(5), 14000 + 380);
+		assert_eq!(PaymentBalances::free_balance(1), 10000);
+		assert_eq!(PaymentBalances::free_balance(99), 1 + 50);
+	});
+}
+
+#[test]
+fn test_max_orders_per_block() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		set_max_orders_per_block(2);
+		for _ in 0..3 {
+			assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		}
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 1000, 10, SellParams::default()));
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 1, 100, 1000, 10, SellParams::default()));
+		assert_eq!(OrdersThisBlock::get(), 2);
+		assert_noop!(
+			NftModule::order_sell(Origin::signed(1), 2, 100, 1000, 10, SellParams::default()),
+			Error::<Test>::BlockOrderLimitReached
+		);
+
+		// 下一个区块计数重置
+		run_to_block(11);
+		assert_eq!(OrdersThisBlock::get(), 0);
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 2, 100, 1000, 10, SellParams::default()));
+	});
+}
+
+#[test]
+fn test_nft_metadata_on_create() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 7));
+		assert_eq!(Nfts::<Test>::get(&0), Some(NftMetadata {
+			title: b"title_value".to_vec(),
+			uri: b"url_value".to_vec(),
+			desc: b"desc_value".to_vec(),
+			content_type: 7,
+			created_at: 10,
+		}));
+		// 链接不能超过MaxUrlLength
+		assert_noop!(
+			NftModule::create(Origin::signed(1), "title_value".into(), vec![b'a'; 65], "desc_value".into(), [0u8; 32], 0),
+			Error::<Test>::UrlTooLong
+		);
+	});
+}
+
+#[test]
+fn test_migrate_legacy_nft_metadata() {
+	use frame_support::traits::OnRuntimeUpgrade;
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		// 新格式的nft
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 7));
+		// 旧格式的nft
+		let legacy = Nft { title: b"old_title".to_vec(), url: b"old_url".to_vec(), desc: b"old_desc".to_vec() };
+		frame_support::storage::unhashed::put(&Nfts::<Test>::hashed_key_for(1), &legacy);
+		NftAccount::<Test>::insert(1, 2);
+		NftMintBlock::<Test>::insert(1, 5);
+		assert!(Nfts::<Test>::get(&1).is_none());
+
+		NftModule::on_runtime_upgrade();
+		assert_eq!(NftModule::storage_version(), Releases::V3_0_0);
+		assert_eq!(Nfts::<Test>::get(&1), Some(NftMetadata {
+			title: b"old_title".to_vec(),
+			uri: b"old_url".to_vec(),
+			desc: b"old_desc".to_vec(),
+			content_type: 0,
+			created_at: 5,
+		}));
+		// 新格式的数据保持不变
+		assert_eq!(Nfts::<Test>::get(&0).unwrap().content_type, 7);
+	});
+}
+
+#[test]
+fn test_relist_cooldown() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		set_relist_cooldown(5);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 1000, 10, SellParams { kind: AuctionKind::FixedPrice, ..Default::default() }));
+		assert_ok!(NftModule::order_buy(Origin::signed(2), 0, 100, None));
+		assert_eq!(LastSoldBlock::<Test>::get(&0), Some(10));
+
+		// 冷却期内不能再次挂单
+		assert_noop!(
+			NftModule::order_sell(Origin::signed(2), 0, 100, 1000, 10, SellParams { kind: AuctionKind::FixedPrice, ..Default::default() }),
+			Error::<Test>::RelistCooldownActive
+		);
+		run_to_block(14);
+		assert_noop!(
+			NftModule::order_sell(Origin::signed(2), 0, 100, 1000, 10, SellParams { kind: AuctionKind::FixedPrice, ..Default::default() }),
+			Error::<Test>::RelistCooldownActive
+		);
+		run_to_block(15);
+		assert_ok!(NftModule::order_sell(Origin::signed(2), 0, 100, 1000, 10, SellParams { kind: AuctionKind::FixedPrice, ..Default::default() }));
+	});
+}
+
+#[test]
+fn test_vote_placed_event() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 1000, 100, SellParams::default()));
+		run_to_block(20);
+		assert_ok!(NftModule::vote_order(Origin::signed(2), 0, 50));
+		// 质押区块长度为到期区块110 - 当前区块20
+		let vote_event = TestEvent::nft_event(RawEvent::VotePlaced(2, 0, 50, 90));
+		assert!(System::events().iter().any(|a| a.event == vote_event));
+		assert_eq!(PaymentBalances::reserved_balance(2), 50);
+	});
+}
+
+#[test]
+fn test_seller_vote_allowed() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 1000, 100, SellParams::default()));
+		// 默认允许卖家质押自己的订单
+		assert_ok!(NftModule::vote_order(Origin::signed(1), 0, 50));
+		assert_eq!(VoterCount::<Test>::get(&0), 1);
+	});
+}
+
+#[test]
+fn test_seller_vote_disallowed() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		set_allow_seller_vote(false);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 1000, 100, SellParams::default()));
+		assert_noop!(NftModule::vote_order(Origin::signed(1), 0, 50), Error::<Test>::SellerCannotVote);
+		// 其他账户不受影响
+		assert_ok!(NftModule::vote_order(Origin::signed(2), 0, 50));
+	});
+}
+
+#[test]
+fn test_validate_order_sell() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::validate_order_sell(1, 0, 100, 1000, 10, SellParams::default()));
+		// 试运行不修改状态
+		assert!(Orders::<Test>::get(&0).is_none());
+		assert_eq!(NextOrderId::<Test>::get(), 0);
+
+		assert_eq!(
+			NftModule::validate_order_sell(2, 0, 100, 1000, 10, SellParams::default()),
+			Err(Error::<Test>::NotNftOwner.into())
+		);
+		assert_eq!(
+			NftModule::validate_order_sell(1, 0, 1000, 100, 10, SellParams::default()),
+			Err(Error::<Test>::OrderPriceIllegal.into())
+		);
+		assert_eq!(
+			NftModule::validate_order_sell(1, 0, 100, 1000, 10, SellParams { kind: AuctionKind::Dutch, settlement_mode: SettlementMode::SecondPrice, ..Default::default() }),
+			Err(Error::<Test>::SecondPriceNotEnglish.into())
+		);
+		assert_eq!(
+			NftModule::validate_order_sell(1, 5, 100, 1000, 10, SellParams::default()),
+			Err(Error::<Test>::NftIdNotExist.into())
+		);
+	});
+}
+
+#[test]
+fn test_validate_order_buy() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 1000, 10, SellParams::default()));
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 1, 100, 1000, 10, SellParams { kind: AuctionKind::FixedPrice, ..Default::default() }));
+		assert_ok!(NftModule::order_buy(Origin::signed(2), 0, 200, None));
+
+		assert_ok!(NftModule::validate_order_buy(0, 300, None));
+		// 试运行不修改状态
+		assert_eq!(Bids::<Test>::get(&0).unwrap().owner, 2);
+		assert_eq!(PaymentBalances::reserved_balance(2), 200);
+
+		assert_eq!(NftModule::validate_order_buy(0, 200, None), Err(Error::<Test>::BelowCurrentBid.into()));
+		assert_eq!(NftModule::validate_order_buy(0, 50, None), Err(Error::<Test>::BelowStartPrice.into()));
+		assert_eq!(NftModule::validate_order_buy(0, 300, Some(150)), Err(Error::<Test>::PriceExceedsMax.into()));
+		assert_eq!(NftModule::validate_order_buy(1, 200, None), Err(Error::<Test>::BiddingNotAllowed.into()));
+		assert_eq!(NftModule::validate_order_buy(5, 200, None), Err(Error::<Test>::OrderNotExist.into()));
+		run_to_block(21);
+		assert_eq!(NftModule::validate_order_buy(1, 100, None), Err(Error::<Test>::OrderNotExist.into()));
+	});
+}
+
+#[test]
+fn test_bid_escrow_full_auction() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		set_use_escrow(true);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 1000, 10, SellParams::default()));
+		let escrow = NftModule::escrow_account(0);
+
+		// 出价转入托管账户, 不锁定在竞价者账户中
+		assert_ok!(NftModule::order_buy(Origin::signed(2), 0, 200, None));
+		assert_eq!(PaymentBalances::free_balance(escrow), 200);
+		assert_eq!(PaymentBalances::free_balance(2), 11000 - 200);
+		assert_eq!(PaymentBalances::reserved_balance(2), 0);
+
+		// 出价被超过时从托管账户退还
+		assert_ok!(NftModule::order_buy(Origin::signed(3), 0, 300, None));
+		assert_eq!(PaymentBalances::free_balance(escrow), 300);
+		assert_eq!(PaymentBalances::free_balance(2), 11000);
+		// 最高出价者加价只转入差额
+		assert_ok!(NftModule::order_buy(Origin::signed(3), 0, 400, None));
+		assert_eq!(PaymentBalances::free_balance(escrow), 400);
+		assert_eq!(PaymentBalances::free_balance(3), 12000 - 400);
+		assert_eq!(TotalReserved::<Test>::get(), 400);
+
+		// 成交时从托管账户支付手续费和卖家所得
+		run_to_block(21);
+		assert_eq!(NftAccount::<Test>::get(&0), 3);
+		assert_eq!(PaymentBalances::free_balance(escrow), 0);
+		assert_eq!(PaymentBalances::free_balance(1), 10000 + 380);
+		assert_eq!(PaymentBalances::free_balance(99), 1 + 20);
+		assert_eq!(PaymentBalances::free_balance(3), 12000 - 400);
+		assert_eq!(TotalReserved::<Test>::get(), 0);
+	});
+}
+
+#[test]
+fn test_bid_escrow_first_bid_below_existential_deposit() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		set_use_escrow(true);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 100000, 10, SellParams::default()));
+		set_payment_existential_deposit(300);
+		let escrow = NftModule::escrow_account(0);
+
+		// 托管账户尚不存在时, 低于最低存在余额的出价无法创建托管账户
+		assert_noop!(NftModule::order_buy(Origin::signed(2), 0, 200, None), Error::<Test>::BidBelowExistentialDeposit);
+		assert_ok!(NftModule::order_buy(Origin::signed(2), 0, 300, None));
+		assert_eq!(PaymentBalances::free_balance(escrow), 300);
+		// 竞价者可以锁定全部可用余额
+		assert_ok!(NftModule::order_buy(Origin::signed(3), 0, 12000, None));
+		assert_eq!(PaymentBalances::free_balance(3), 0);
+		assert_eq!(PaymentBalances::free_balance(2), 11000);
+	});
+}
+
+#[test]
+fn test_bid_escrow_failed_refund_is_claimable() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		set_use_escrow(true);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 1000, 10, SellParams::default()));
+		assert_ok!(NftModule::order_buy(Origin::signed(2), 0, 200, None));
+		// 托管账户资金丢失, 结算和退还都会失败
+		let escrow = NftModule::escrow_account(0);
+		PaymentBalances::make_free_balance_be(&escrow, 0);
+
+		run_to_block(21);
+		assert!(FailedOrders::<Test>::contains_key(0));
+		assert_eq!(PendingBidRefunds::<Test>::get(0, 2), 200);
+		let pending_event = TestEvent::nft_event(RawEvent::BidRefundPending(2, 0, 200));
+		assert!(System::events().iter().any(|a| a.event == pending_event));
+		// 退款仍计入锁定总额
+		assert_eq!(ReservedByAccount::<Test>::get(2), 200);
+		assert_eq!(NftModule::total_value_locked(), 200);
+
+		// 托管账户资金不足时领取失败, 退款保留
+		assert!(NftModule::claim_bid_refund(Origin::signed(2), 0).is_err());
+		assert_eq!(PendingBidRefunds::<Test>::get(0, 2), 200);
+		assert_noop!(NftModule::claim_bid_refund(Origin::signed(3), 0), Error::<Test>::NoBidRefund);
+
+		PaymentBalances::make_free_balance_be(&escrow, 200);
+		assert_ok!(NftModule::claim_bid_refund(Origin::signed(2), 0));
+		assert_eq!(PaymentBalances::free_balance(2), 11000);
+		assert_eq!(PendingBidRefunds::<Test>::get(0, 2), 0);
+		assert_eq!(NftModule::total_value_locked(), 0);
+	});
+}
+
+#[test]
+fn test_order_on_removed_nft_is_not_resurrected() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 1000, 10, SellParams::default()));
+		assert_ok!(NftModule::order_buy(Origin::signed(2), 0, 200, None));
+
+		// 在订单存在时强制移除nft
+		Nfts::<Test>::remove(0);
+		assert_noop!(NftModule::order_buy(Origin::signed(3), 0, 300, None), Error::<Test>::NftIdNotExist);
+
+		// 结算失败, 竞价被退还, nft不会复活
+		run_to_block(21);
+		assert!(FailedOrders::<Test>::contains_key(0));
+		assert!(Nfts::<Test>::get(&0).is_none());
+		assert_eq!(NftAccount::<Test>::get(&0), 1);
+		assert_eq!(PaymentBalances::reserved_balance(2), 0);
+		assert_eq!(PaymentBalances::free_balance(2), 11000);
+	});
+}
+
+#[test]
+fn test_cancel_order_with_bid_compensates_bidder() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		set_listing_deposit(5);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 1000, 10, SellParams::default()));
+		assert_ok!(NftModule::order_buy(Origin::signed(2), 0, 200, None));
+
+		assert_noop!(NftModule::cancel_order(Origin::signed(2), 0), Error::<Test>::NotNftOwner);
+		assert_ok!(NftModule::cancel_order(Origin::signed(1), 0));
+
+		// 补偿为出价的10%: 押金5全部扣除, 其余15从卖家可用余额支付
+		let expected_event = TestEvent::nft_event(RawEvent::CancelPenaltyPaid(1, 0, 2, 20));
+		assert!(System::events().iter().any(|a| a.event == expected_event));
+		assert_eq!(PaymentBalances::free_balance(1), 9985);
+		assert_eq!(PaymentBalances::reserved_balance(1), 0);
+		assert_eq!(PaymentBalances::free_balance(2), 11020);
+		assert_eq!(PaymentBalances::reserved_balance(2), 0);
+		assert!(Orders::<Test>::get(0).is_none());
+		assert_eq!(NftAccount::<Test>::get(&0), 1);
+	});
+}
+
+#[test]
+fn test_order_settlement_batch_skips_unready_orders() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		for _ in 0..3 {
+			assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		}
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 1000, 10, SellParams::default()));
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 1, 100, 1000, 10, SellParams::default()));
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 2, 100, 1000, 50, SellParams::default()));
+		assert_ok!(NftModule::order_buy(Origin::signed(2), 0, 200, None));
+		assert_ok!(NftModule::order_buy(Origin::signed(3), 2, 200, None));
+
+		assert_noop!(NftModule::order_settlement_batch(Origin::signed(5), vec![0; 6]), Error::<Test>::SettlementBatchTooLarge);
+
+		// 不经过on_initialize, 订单0和1到期, 订单2未到期, 订单9不存在
+		System::set_block_number(21);
+		assert_ok!(NftModule::order_settlement_batch(Origin::signed(5), vec![0, 1, 2, 9]));
+		let expected_event = TestEvent::nft_event(RawEvent::SettlementBatch(5, 2, 2));
+		assert!(System::events().iter().any(|a| a.event == expected_event));
+		assert_eq!(NftAccount::<Test>::get(&0), 2);
+		assert!(Orders::<Test>::get(0).is_none());
+		assert!(Orders::<Test>::get(1).is_none());
+		assert!(Orders::<Test>::get(2).is_some());
+		assert_eq!(NftAccount::<Test>::get(&2), 1);
+	});
+}
+
+#[test]
+fn test_min_voters_for_payout() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		set_min_voters_for_payout(3);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+
+		// 只有一个质押者, 低于最少人数, 不分配利润
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 1000, 100, SellParams::default()));
+		assert_ok!(NftModule::vote_order(Origin::signed(2), 0, 200));
+		assert_ok!(NftModule::order_buy(Origin::signed(5), 0, 500, None));
+		run_to_block(111);
+		assert!(Orders::<Test>::get(&0).is_none());
+		assert_eq!(PaymentBalances::free_balance(2), 11000);
+		assert_eq!(PaymentBalances::reserved_balance(2), 0);
+
+		// 三个质押者, 达到最少人数, 分配利润
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 1, 100, 1000, 100, SellParams::default()));
+		assert_ok!(NftModule::vote_order(Origin::signed(2), 1, 200));
+		assert_ok!(NftModule::vote_order(Origin::signed(3), 1, 300));
+		assert_ok!(NftModule::vote_order(Origin::signed(4), 1, 400));
+		assert_ok!(NftModule::order_buy(Origin::signed(5), 1, 500, None));
+		run_to_block(212);
+		assert!(Orders::<Test>::get(&1).is_none());
+		assert!(PaymentBalances::free_balance(2) > 11000);
+		assert!(PaymentBalances::free_balance(3) > 12000);
+		assert!(PaymentBalances::free_balance(4) > 13000);
+	});
+}
+
+#[test]
+fn test_migrate_legacy_orders() {
+	use frame_support::traits::OnRuntimeUpgrade;
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		// 初始版本的订单
+		let legacy: LegacyOrderOf<Test> = LegacyOrder { order_id: 0, start_price: 100, end_price: 1000, nft_id: 0, create_block: 5, keep_block_num: 20, owner: 1 };
+		frame_support::storage::unhashed::put(&Orders::<Test>::hashed_key_for(0), &legacy);
+		NftOrder::<Test>::insert(0, 0);
+		// 旧版本已有的出价和质押
+		assert_ok!(PaymentBalances::reserve(&2, 200));
+		Bids::<Test>::insert(0, Bid { order_id: 0, price: 200, owner: 2 });
+		assert_ok!(PaymentBalances::reserve(&3, 50));
+		Votes::<Test>::insert(0, vec![Vote { order_id: 0, amount: 50, keep_block_num: 10, owner: 3 }]);
+		assert!(Orders::<Test>::get(&0).is_none());
+		assert_eq!(NftModule::storage_version(), Releases::V1_0_0);
+
+		NftModule::on_runtime_upgrade();
+		assert_eq!(NftModule::storage_version(), Releases::V3_0_0);
+		let order = Orders::<Test>::get(&0).unwrap();
+		assert_eq!(order.expire_block, 25);
+		assert_eq!(order.start_block, 5);
+		assert_eq!(order.kind, AuctionKind::English);
+		assert_eq!(order.settlement_mode, SettlementMode::FirstPrice);
+		assert!(order.payees.is_empty());
+		assert!(ActiveOrders::<Test>::contains_key(0));
+		assert_eq!(ExpiringOrders::<Test>::get(26), vec![0]);
+		// 补记锁定资金统计
+		assert_eq!(ReservedByAccount::<Test>::get(2), 200);
+		assert_eq!(ReservedByAccount::<Test>::get(3), 50);
+		assert_eq!(NftModule::total_value_locked(), 250);
+
+		// 已经是最新版本时再次升级不做任何修改
+		NftModule::on_runtime_upgrade();
+		assert_eq!(ExpiringOrders::<Test>::get(26), vec![0]);
+		assert_eq!(OrderCountByOwner::<Test>::get(1), 1);
+		assert_eq!(NftModule::total_value_locked(), 250);
+	});
+}
+
+#[test]
+fn test_order_status() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 1000, 10, SellParams::default()));
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 1, 100, 1000, 10, SellParams::default()));
+		assert_eq!(NftModule::order_status(9), None);
+		assert_eq!(NftModule::order_status(0), Some(OrderStatus::Live));
+		assert_ok!(NftModule::order_buy(Origin::signed(2), 0, 200, None));
+		assert_eq!(NftModule::order_status(0), Some(OrderStatus::Live));
+
+		// 不经过on_initialize, 到期后等待结算
+		System::set_block_number(21);
+		assert_eq!(NftModule::order_status(0), Some(OrderStatus::EndedWithBid));
+		assert_eq!(NftModule::order_status(1), Some(OrderStatus::EndedNoBid));
+	});
+}
+
+#[test]
+fn test_settlement_reward_paid_once() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 1000, 10, SellParams::default()));
+		assert_ok!(NftModule::order_buy(Origin::signed(2), 0, 200, None));
+
+		// 不经过on_initialize, 直接手动结算, 结算人获得成交价1%的奖励
+		System::set_block_number(21);
+		assert_ok!(NftModule::order_settlement(Origin::signed(3), 0));
+		assert_eq!(PaymentBalances::free_balance(3), 12002);
+
+		// 订单已经结算, 再次结算失败且不再奖励
+		assert_noop!(NftModule::order_settlement(Origin::signed(3), 0), Error::<Test>::OrderNotExist.with_weight(0));
+		assert_eq!(PaymentBalances::free_balance(3), 12002);
+	});
+}
+
+#[test]
+fn test_seller_settlement_not_rewarded() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 1000, 10, SellParams::default()));
+		assert_ok!(PaymentBalances::transfer(Origin::signed(5), 99, 100));
+
+		// 卖家自行结算流拍的订单, 平台账户不奖励
+		System::set_block_number(21);
+		assert_ok!(NftModule::order_settlement(Origin::signed(1), 0));
+		assert!(Orders::<Test>::get(&0).is_none());
+		assert_eq!(PaymentBalances::free_balance(1), 10000);
+		assert_eq!(PaymentBalances::free_balance(99), 101);
+	});
+}
+
+#[test]
+fn test_max_listings_per_nft() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		set_max_listings_per_nft(2);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		for order_id in 0..2 {
+			assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 1000, 10, SellParams::default()));
+			assert_ok!(NftModule::cancel_order(Origin::signed(1), order_id));
+		}
+		assert_eq!(NftListCount::<Test>::get(0), 2);
+		assert_noop!(
+			NftModule::order_sell(Origin::signed(1), 0, 100, 1000, 10, SellParams::default()),
+			Error::<Test>::ListingLimitReached
+		);
+	});
+}
+
+#[test]
+fn test_ownership_errors() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+
+		// nft不存在
+		assert_noop!(NftModule::remove(Origin::signed(1), 5), Error::<Test>::NftIdNotExist);
+		assert_noop!(NftModule::transfer(Origin::signed(1), 2, 5), Error::<Test>::NftIdNotExist);
+		assert_noop!(
+			NftModule::order_sell(Origin::signed(1), 5, 100, 1000, 10, SellParams::default()),
+			Error::<Test>::NftIdNotExist
+		);
+
+		// 调用者不是nft所有者
+		assert_noop!(NftModule::remove(Origin::signed(2), 0), Error::<Test>::NotNftOwner);
+		assert_noop!(NftModule::transfer(Origin::signed(2), 3, 0), Error::<Test>::NotNftOwner);
+		assert_noop!(
+			NftModule::order_sell(Origin::signed(2), 0, 100, 1000, 10, SellParams::default()),
+			Error::<Test>::NotNftOwner
+		);
+	});
+}
+
+#[test]
+fn test_compute_shares_days_per_year() {
+	new_test_ext().execute_with(|| {
+		set_days_per_year(730);
+		// 拍卖时长1天, 初始股权数 = 100 * 0.2 / 1 * 730 = 14600
+		let votes = vec![test_vote(2, 100, 14400), test_vote(3, 200, 7200)];
+		let shares = NftModule::compute_shares(&test_order(14400), 100, &votes).unwrap();
+		assert_share(&shares[0], 2, 100.0);
+		// 汇率 14600 / (14600 + 100), 高于每年365天时的 7300 / 7400
+		assert_share(&shares[1], 3, 100.0 * 14600.0 / 14700.0);
+	});
+}
+
+#[test]
+fn test_underfunded_vote_and_bid_errors() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 100_000, 100, SellParams::default()));
+
+		// 没有余额的账户
+		assert_noop!(NftModule::vote_order(Origin::signed(6), 0, 100), Error::<Test>::InsufficientBalanceForVote);
+		assert_noop!(NftModule::order_buy(Origin::signed(6), 0, 200, None), Error::<Test>::InsufficientBalanceForBid);
+		// 出价超过可用余额
+		assert_noop!(NftModule::order_buy(Origin::signed(2), 0, 12000, None), Error::<Test>::InsufficientBalanceForBid);
+		assert_ok!(NftModule::order_buy(Origin::signed(2), 0, 10000, None));
+	});
+}
+
+#[test]
+fn test_transfer_order_ownership() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		set_listing_deposit(5);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 1000, 10, SellParams::default()));
+		assert_ok!(NftModule::order_buy(Origin::signed(2), 0, 200, None));
+
+		assert_noop!(NftModule::transfer_order_ownership(Origin::signed(3), 0, 3), Error::<Test>::NotNftOwner);
+		assert_ok!(NftModule::transfer_order_ownership(Origin::signed(1), 0, 4));
+		let expected_event = TestEvent::nft_event(RawEvent::OrderOwnershipTransferred(0, 1, 4));
+		assert!(System::events().iter().any(|a| a.event == expected_event));
+		assert_eq!(Orders::<Test>::get(0).unwrap().owner, 4);
+		assert_eq!(NftAccount::<Test>::get(&0), 1);
+		assert_eq!(PaymentBalances::reserved_balance(1), 0);
+		assert_eq!(PaymentBalances::reserved_balance(4), 5);
+
+		// 成交后新卖家获得扣除5%手续费后的成交价和押金, nft从原所有者转给买家
+		run_to_block(21);
+		assert_eq!(NftAccount::<Test>::get(&0), 2);
+		assert_eq!(PaymentBalances::free_balance(1), 9995);
+		assert_eq!(PaymentBalances::free_balance(4), 13000 + 190 + 5);
+		assert_eq!(PaymentBalances::reserved_balance(4), 0);
+	});
+}
+
+#[test]
+fn test_raise_own_bid_needs_only_delta() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 100_000, 100, SellParams::default()));
+		assert_ok!(NftModule::order_buy(Origin::signed(2), 0, 10000, None));
+		assert_eq!(PaymentBalances::free_balance(2), 1000);
+
+		// 加价只需要锁定差额, 差额超过可用余额时在修改状态前拒绝
+		assert_noop!(NftModule::order_buy(Origin::signed(2), 0, 11001, None), Error::<Test>::InsufficientBalanceForBid);
+		assert_ok!(NftModule::order_buy(Origin::signed(2), 0, 10999, None));
+		assert_eq!(PaymentBalances::free_balance(2), 1);
+		assert_eq!(PaymentBalances::reserved_balance(2), 10999);
+		assert_eq!(Bids::<Test>::get(0).unwrap().price, 10999);
+	});
+}
+
+#[test]
+fn test_max_total_votes_per_order() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		set_max_total_votes_per_order(500);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 1000, 100, SellParams::default()));
+		assert_ok!(NftModule::vote_order(Origin::signed(2), 0, 200));
+		assert_ok!(NftModule::vote_order(Origin::signed(3), 0, 300));
+		assert_eq!(OrderVoteTotal::<Test>::get(0), 500);
+		assert_noop!(NftModule::vote_order(Origin::signed(4), 0, 100), Error::<Test>::OrderVoteCapReached);
+
+		// 减少质押后可以继续质押
+		assert_ok!(NftModule::reduce_vote(Origin::signed(3), 0, 100));
+		assert_ok!(NftModule::vote_order(Origin::signed(4), 0, 100));
+		assert_eq!(OrderVoteTotal::<Test>::get(0), 500);
+	});
+}
+
+#[test]
+fn test_all_nft_ids() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		for _ in 0..3 {
+			assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		}
+		// 按存储键顺序遍历, 排序后比较
+		let mut ids = NftModule::all_nft_ids();
+		ids.sort();
+		assert_eq!(ids, vec![0, 1, 2]);
+
+		// 分页查询结果与全部查询一致
+		let (first, next) = NftModule::nft_ids_paged(None, 2);
+		assert_eq!(first.len(), 2);
+		let (second, last) = NftModule::nft_ids_paged(next, 2);
+		assert_eq!(second.len(), 1);
+		assert_eq!(last, None);
+		let mut paged: Vec<u32> = first.into_iter().chain(second).collect();
+		paged.sort();
+		assert_eq!(paged, ids);
+	});
+}
+
+#[test]
+fn test_early_settlement_releases_votes_immediately() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 1000, 100, SellParams::default()));
+		assert_ok!(NftModule::vote_order(Origin::signed(2), 0, 200));
+		assert_ok!(NftModule::order_buy(Origin::signed(3), 0, 1000, None));
+		assert!(Orders::<Test>::get(&0).is_none());
+		assert_eq!(PaymentBalances::reserved_balance(2), 0);
+		assert_eq!(LockedVotes::<Test>::get(0, 2), None);
+	});
+}
+
+#[test]
+fn test_honor_full_vote_lock() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		set_honor_full_vote_lock(true);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 1000, 100, SellParams::default()));
+		assert_ok!(NftModule::vote_order(Origin::signed(2), 0, 200));
+		assert_ok!(NftModule::order_buy(Origin::signed(3), 0, 1000, None));
+
+		// 提前成交, 质押继续锁定到原定的到期区块110
+		assert!(Orders::<Test>::get(&0).is_none());
+		assert_eq!(PaymentBalances::reserved_balance(2), 200);
+		assert_eq!(LockedVotes::<Test>::get(0, 2), Some((200, 110)));
+		assert_noop!(NftModule::release_vote(Origin::signed(2), 0), Error::<Test>::VoteStillLocked);
+		assert_noop!(NftModule::release_vote(Origin::signed(4), 0), Error::<Test>::NoLockedVote);
+
+		run_to_block(110);
+		assert_ok!(NftModule::release_vote(Origin::signed(2), 0));
+		assert_eq!(PaymentBalances::reserved_balance(2), 0);
+		assert_eq!(LockedVotes::<Test>::get(0, 2), None);
+	});
+}
+
+#[test]
+fn test_order_sell_rejects_inconsistent_nft_indices() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+
+		// 所有者索引缺失
+		NftAccount::<Test>::remove(0);
+		assert_noop!(
+			NftModule::order_sell(Origin::signed(1), 0, 100, 1000, 10, SellParams::default()),
+			Error::<Test>::NftIdNotExist
+		);
+		// nft详情缺失
+		Nfts::<Test>::remove(1);
+		assert_noop!(
+			NftModule::order_sell(Origin::signed(1), 1, 100, 1000, 10, SellParams::default()),
+			Error::<Test>::NftIdNotExist
+		);
+		assert!(NftOrder::<Test>::get(0).is_none());
+		assert!(NftOrder::<Test>::get(1).is_none());
+	});
+}
+
+#[test]
+fn test_cancellation_fee_without_bid() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		set_cancellation_fee(10);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 1000, 10, SellParams::default()));
+		assert_ok!(NftModule::cancel_order(Origin::signed(1), 0));
+		assert_eq!(PaymentBalances::free_balance(1), 9990);
+		assert_eq!(PaymentBalances::free_balance(96), 10);
+	});
+}
+
+#[test]
+fn test_cancellation_fee_waived_with_bid() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		set_cancellation_fee(10);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 1000, 10, SellParams::default()));
+		assert_ok!(NftModule::order_buy(Origin::signed(2), 0, 200, None));
+		assert_ok!(NftModule::cancel_order(Origin::signed(1), 0));
+		// 只向最高出价者支付出价10%的补偿, 不收取撤单手续费
+		assert_eq!(PaymentBalances::free_balance(1), 9980);
+		assert_eq!(PaymentBalances::free_balance(2), 11020);
+		assert_eq!(PaymentBalances::free_balance(96), 0);
+	});
+}
+
+#[test]
+fn test_transfer_order_ownership_clears_payees() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 1000, 10, SellParams { payees: vec![(3, Permill::from_percent(60)), (5, Permill::from_percent(40))], ..Default::default() }));
+		assert_ok!(NftModule::order_buy(Origin::signed(2), 0, 200, None));
+		assert_ok!(NftModule::transfer_order_ownership(Origin::signed(1), 0, 4));
+		assert!(Orders::<Test>::get(0).unwrap().payees.is_empty());
+
+		// 成交所得全部归新卖家, 原卖家的收款账户不再分配
+		run_to_block(21);
+		assert_eq!(PaymentBalances::free_balance(4), 13000 + 190);
+		assert_eq!(PaymentBalances::free_balance(3), 12000);
+		assert_eq!(PaymentBalances::free_balance(5), 14000);
+	});
+}
+
+#[test]
+fn test_root_freeze_only_lifted_by_root() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		assert_ok!(NftModule::set_frozen(Origin::root(), 0, true));
+		assert!(RootFrozenNfts::<Test>::get(0));
+
+		// 所有者不能解除root的冻结
+		assert_noop!(NftModule::set_frozen(Origin::signed(1), 0, false), Error::<Test>::FrozenByRoot);
+		assert_ok!(NftModule::set_frozen(Origin::root(), 0, false));
+		assert!(!FrozenNfts::<Test>::contains_key(0));
+		assert!(!RootFrozenNfts::<Test>::contains_key(0));
+
+		// 所有者冻结的nft所有者可以自行解冻
+		assert_ok!(NftModule::set_frozen(Origin::signed(1), 0, true));
+		assert_ok!(NftModule::set_frozen(Origin::signed(1), 0, false));
+	});
+}
+
+#[test]
+fn test_payee_split_still_pays_voters() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		// 成交所得全部归收款账户3, 卖家只剩最小存款
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 1000, 100, SellParams { payees: vec![(3, Permill::one())], ..Default::default() }));
+		assert_ok!(PaymentBalances::transfer(Origin::signed(1), 6, 9999));
+		assert_ok!(NftModule::vote_order(Origin::signed(2), 0, 200));
+		assert_ok!(NftModule::order_buy(Origin::signed(5), 0, 500, None));
+
+		run_to_block(111);
+		assert!(Orders::<Test>::get(&0).is_none());
+		// 质押者的利润从购买者锁定的资金中支付, 并获得相同数量的分红股份
+		let profit = PaymentBalances::free_balance(2) - 11000;
+		assert!(profit > 0);
+		assert_eq!(NftShares::<Test>::get(0, 2), profit);
+		// 收款账户获得扣除5%手续费和质押者利润后的部分, 卖家的余额不受影响
+		assert_eq!(PaymentBalances::free_balance(3), 12000 + 475 - profit);
+		assert_eq!(PaymentBalances::free_balance(1), 1);
+		assert_eq!(PaymentBalances::free_balance(5), 14000 - 500);
+		assert_eq!(PaymentBalances::reserved_balance(5), 0);
+	});
+}
+
+#[test]
+fn test_close_collection() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		set_listing_deposit(10);
+		for _ in 0..3 {
+			assert_ok!(NftModule::create(Origin::signed(1), "title_value".into(), "url_value".into(), "desc_value".into(), [0u8; 32], 0));
+		}
+		assert_ok!(NftModule::create_collection(Origin::signed(1)));
+		let created_event = TestEvent::nft_event(RawEvent::CollectionCreated(1, 0));
+		assert!(System::events().iter().any(|a| a.event == created_event));
+		assert_noop!(NftModule::add_to_collection(Origin::signed(2), 0, 0), Error::<Test>::NotCollectionOwner);
+		for nft_id in 0..3 {
+			assert_ok!(NftModule::add_to_collection(Origin::signed(1), 0, nft_id));
+		}
+		assert_noop!(NftModule::add_to_collection(Origin::signed(1), 0, 0), Error::<Test>::NftInCollection);
+		assert_eq!(CollectionNfts::<Test>::get(0), vec![0, 1, 2]);
+
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 0, 100, 1000, 10, SellParams::default()));
+		assert_ok!(NftModule::order_sell(Origin::signed(1), 1, 100, 1000, 10, SellParams::default()));
+		assert_ok!(NftModule::order_buy(Origin::signed(2), 0, 200, None));
+		assert_ok!(NftModule::order_buy(Origin::signed(3), 1, 300, None));
+		assert_ok!(NftModule::vote_order(Origin::signed(4), 1, 100));
+		assert_noop!(NftModule::close_collection(Origin::signed(2), 0), Error::<Test>::NotCollectionOwner);
+
+		// 每次调用处理一个nft, 第一次调用后合集已关闭, 不能再挂单
+		assert_ok!(NftModule::close_collection(Origin::signed(1), 0));
+		assert!(Orders::<Test>::get(&0).is_none());
+		assert!(Orders::<Test>::get(&1).is_some());
+		assert_eq!(PaymentBalances::free_balance(2), 11000);
+		let closing_event = TestEvent::nft_event(RawEvent::CollectionClosing(0, 1));
+		assert!(System::events().iter().any(|a| a.event == closing_event));
+		assert_noop!(
+			NftModule::order_sell(Origin::signed(1), 2, 100, 1000, 10, SellParams::default()),
+			Error::<Test>::CollectionClosed
+		);
+
+		// 从上次的位置继续撤销订单
+		assert_ok!(NftModule::close_collection(Origin::signed(1), 0));
+		assert!(Orders::<Test>::get(&1).is_none());
+		assert_eq!(PaymentBalances::free_balance(3), 12000);
+		assert_eq!(PaymentBalances::reserved_balance(4), 0);
+		assert_ok!(NftModule::close_collection(Origin::signed(1), 0));
+		let closed_event = TestEvent::nft_event(RawEvent::CollectionClosed(0));
+		assert!(System::events().iter().any(|a| a.event == closed_event));
+		assert_noop!(NftModule::close_collection(Origin::signed(1), 0), Error::<Test>::CollectionAlreadyClosed);
+
+		// 挂单押金全部退还
+		assert_eq!(PaymentBalances::free_balance(1), 10000);
+		assert_eq!(PaymentBalances::reserved_balance(1), 0);
+		assert_eq!(NftModule::total_value_locked(), 0);
+	});
+}
@@ -9,11 +9,11 @@ include!(concat!(env!("OUT_DIR"), "/wasm_binary.rs"));
 use sp_std::prelude::*;
 use sp_core::{crypto::KeyTypeId, OpaqueMetadata};
 use sp_runtime::{
-	ApplyExtrinsicResult, generic, create_runtime_str, impl_opaque_keys, MultiSignature,
+	ApplyExtrinsicResult, generic, create_runtime_str, impl_opaque_keys, MultiSignature, ModuleId,
 	transaction_validity::{TransactionValidity, TransactionSource},
 };
 use sp_runtime::traits::{
-	BlakeTwo256, Block as BlockT, Verify, IdentifyAccount, NumberFor, Saturating,
+	BlakeTwo256, Block as BlockT, Verify, IdentifyAccount, NumberFor, Saturating, AccountIdConversion,
 };
 use sp_api::impl_runtime_apis;
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
@@ -57,6 +57,12 @@ pub type AccountIndex = u32;
 /// Balance of an account.
 pub type Balance = u128;
 
+/// Identifier of an nft.
+pub type NftId = u128;
+
+/// Identifier of an nft auction order.
+pub type OrderId = u128;
+
 /// Index of a transaction in the chain.
 pub type Index = u32;
 
@@ -94,10 +100,10 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	spec_name: create_runtime_str!("nft-swap"),
 	impl_name: create_runtime_str!("nft-swap"),
 	authoring_version: 1,
-	spec_version: 100,
+	spec_version: 101,
 	impl_version: 1,
 	apis: RUNTIME_API_VERSIONS,
-	transaction_version: 1,
+	transaction_version: 2,
 };
 
 pub const MILLISECS_PER_BLOCK: u64 = 6000;
@@ -271,6 +277,55 @@ parameter_types! {
 	pub const FixRate: f64 = 0.2;
 	pub const ProfitRate: f64 = 0.2;
 	pub const DayBlockNum: BlockNumber = DAYS;
+	pub const MaxAutoSettlements: u32 = 10;
+	pub const PlatformFeeRate: Permill = Permill::from_percent(2);
+	pub const NftFeeModuleId: ModuleId = ModuleId(*b"nft/fees");
+	pub NftFeeAccount: AccountId = NftFeeModuleId::get().into_account();
+	pub const DividendRate: Permill = Permill::from_percent(5);
+	pub const NftDividendModuleId: ModuleId = ModuleId(*b"nft/divd");
+	pub NftDividendAccount: AccountId = NftDividendModuleId::get().into_account();
+	pub const NftTreasuryModuleId: ModuleId = ModuleId(*b"nft/trsy");
+	pub NftTreasuryAccount: AccountId = NftTreasuryModuleId::get().into_account();
+	pub const VoterConsolation: Permill = Permill::zero();
+	// U64F64整数部分最大约为1.8e19, 分润算法中的价格不能超过该值
+	pub const MaxOrderPrice: Balance = 100_000 * DOLLARS;
+	pub const AntiSnipeWindow: BlockNumber = 10 * MINUTES;
+	pub const MaxOrdersPerAccount: u32 = 100;
+	pub const MintLockPeriod: BlockNumber = 0;
+	pub const MaxShareStock: u64 = 1 << 62;
+	pub const MaxAutoRelists: u32 = 3;
+	pub const MinVoteLockBlocks: BlockNumber = 10 * MINUTES;
+	pub const MaxBidHistory: u32 = 50;
+	pub const SettlementReward: Permill = Permill::from_parts(5_000);
+	pub const CancelSettlementReward: Balance = 0;
+	pub const MaxProvenanceEntries: u32 = 100;
+	pub const MaxQueuedBids: u32 = 3;
+	pub const ListingDeposit: Balance = 1 * DOLLARS;
+	// 模块子账户没有私钥, 默认不允许降低订单手续费, 需要推广时替换为运营账户
+	pub NftFeeAdmin: AccountId = NftFeeModuleId::get().into_sub_account("admin");
+	pub const MaxBundleSize: u32 = 20;
+	pub const PriceTick: Balance = 1;
+	pub const UniqueUrls: bool = false;
+	pub const IdExhaustionThreshold: u32 = 1_000_000;
+	pub const BuyoutCooldown: BlockNumber = 0;
+	pub const BuyoutCancelPenalty: Permill = Permill::from_percent(5);
+	pub const MaxReservedPerAccount: Balance = Balance::max_value();
+	pub const MaxOrdersPerBlock: u32 = 100;
+	pub const MaxUrlLength: u32 = 256;
+	pub const RelistCooldown: BlockNumber = 0;
+	pub const AllowSellerVote: bool = true;
+	pub const UseEscrow: bool = false;
+	pub const NftEscrowModuleId: ModuleId = ModuleId(*b"nft/escr");
+	pub const CancelPenalty: Permill = Permill::from_percent(5);
+	pub const MaxSettlementBatch: u32 = 50;
+	pub const MinVotersForPayout: u32 = 1;
+	pub const MaxListingsPerNft: u32 = u32::max_value();
+	pub const DaysPerYear: u32 = 365;
+	pub const MaxTotalVotesPerOrder: Balance = Balance::max_value();
+	pub const HonorFullVoteLock: bool = false;
+	pub const CancellationFee: Balance = 1 * DOLLARS;
+	pub const MaxCollectionSize: u32 = 1000;
+	pub const CollectionCloseBatch: u32 = 20;
 }
 impl pallet_nft::Trait for Runtime {
 	type Event = Event;
@@ -281,9 +336,54 @@ impl pallet_nft::Trait for Runtime {
 	type FixRate = FixRate;
 	type ProfitRate = ProfitRate;
 	type DayBlockNum = DayBlockNum;
-	type NftId = u128;
-	type OrderId = u128;
+	type MaxAutoSettlements = MaxAutoSettlements;
+	type PlatformFeeRate = PlatformFeeRate;
+	type FeeAccount = NftFeeAccount;
+	type DividendRate = DividendRate;
+	type DividendAccount = NftDividendAccount;
+	type VoterConsolation = VoterConsolation;
+	type MaxOrderPrice = MaxOrderPrice;
+	type AntiSnipeWindow = AntiSnipeWindow;
+	type MaxOrdersPerAccount = MaxOrdersPerAccount;
+	type MintLockPeriod = MintLockPeriod;
+	type MaxShareStock = MaxShareStock;
+	type MaxAutoRelists = MaxAutoRelists;
+	type MinVoteLockBlocks = MinVoteLockBlocks;
+	type MaxBidHistory = MaxBidHistory;
+	type SettlementReward = SettlementReward;
+	type CancelSettlementReward = CancelSettlementReward;
+	type MaxProvenanceEntries = MaxProvenanceEntries;
+	type MaxQueuedBids = MaxQueuedBids;
+	type ListingDeposit = ListingDeposit;
+	type FeeAdmin = NftFeeAdmin;
+	type MaxBundleSize = MaxBundleSize;
+	type PriceTick = PriceTick;
+	type TreasuryAccount = NftTreasuryAccount;
+	type UniqueUrls = UniqueUrls;
+	type IdExhaustionThreshold = IdExhaustionThreshold;
+	type BuyoutCooldown = BuyoutCooldown;
+	type BuyoutCancelPenalty = BuyoutCancelPenalty;
+	type MaxReservedPerAccount = MaxReservedPerAccount;
+	type MaxOrdersPerBlock = MaxOrdersPerBlock;
+	type MaxUrlLength = MaxUrlLength;
+	type RelistCooldown = RelistCooldown;
+	type AllowSellerVote = AllowSellerVote;
+	type UseEscrow = UseEscrow;
+	type EscrowModuleId = NftEscrowModuleId;
+	type CancelPenalty = CancelPenalty;
+	type MaxSettlementBatch = MaxSettlementBatch;
+	type MinVotersForPayout = MinVotersForPayout;
+	type MaxListingsPerNft = MaxListingsPerNft;
+	type DaysPerYear = DaysPerYear;
+	type MaxTotalVotesPerOrder = MaxTotalVotesPerOrder;
+	type HonorFullVoteLock = HonorFullVoteLock;
+	type CancellationFee = CancellationFee;
+	type MaxCollectionSize = MaxCollectionSize;
+	type CollectionCloseBatch = CollectionCloseBatch;
+	type NftId = NftId;
+	type OrderId = OrderId;
 	type Currency = Balances;
+	type PaymentCurrency = Balances;
 }
 
 // Create the runtime by composing the FRAME pallets that were previously configured.
@@ -465,6 +565,76 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl pallet_nft_runtime_api::NftApi<Block, AccountId, NftId, OrderId, Balance, BlockNumber> for Runtime {
+		fn simulate_shares(
+			order_id: OrderId,
+			who: AccountId,
+			amount: Balance,
+			keep_block_num: BlockNumber,
+		) -> Vec<(AccountId, u128)> {
+			NftModule::simulate_shares(order_id, who, amount, keep_block_num)
+		}
+
+		fn orders_paged(
+			start_key: Option<OrderId>,
+			limit: u32,
+		) -> (Vec<(OrderId, pallet_nft::OrderOf<Runtime>)>, Option<OrderId>) {
+			NftModule::orders_paged(start_key, limit)
+		}
+
+		fn order_detail(order_id: OrderId) -> Option<pallet_nft::OrderDetailOf<Runtime>> {
+			NftModule::order_detail(order_id)
+		}
+
+		fn settleable_orders() -> Vec<OrderId> {
+			NftModule::settleable_orders()
+		}
+
+		fn total_value_locked() -> Balance {
+			NftModule::total_value_locked()
+		}
+
+		fn provenance(nft_id: NftId) -> Vec<(AccountId, BlockNumber)> {
+			NftModule::provenance(nft_id)
+		}
+
+		fn voter_count(order_id: OrderId) -> u32 {
+			NftModule::voter_count(order_id)
+		}
+
+		fn blocks_until_settlement(order_id: OrderId) -> Option<BlockNumber> {
+			NftModule::blocks_until_settlement(order_id)
+		}
+
+		fn vote_weight(order_id: OrderId, amount: Balance, keep_block_num: BlockNumber) -> u128 {
+			NftModule::vote_weight(order_id, amount, keep_block_num)
+		}
+
+		fn sales_stats(nft_id: NftId) -> (u32, Balance, Balance) {
+			NftModule::sales_stats(nft_id)
+		}
+
+		fn validate_order_sell(who: AccountId, nft_id: NftId, start_price: Balance, end_price: Balance, keep_block_num: BlockNumber, params: pallet_nft::SellParams<AccountId, BlockNumber>) -> Result<(), sp_runtime::DispatchError> {
+			NftModule::validate_order_sell(who, nft_id, start_price, end_price, keep_block_num, params)
+		}
+
+		fn validate_order_buy(order_id: OrderId, price: Balance, max_price: Option<Balance>) -> Result<(), sp_runtime::DispatchError> {
+			NftModule::validate_order_buy(order_id, price, max_price)
+		}
+
+		fn order_status(order_id: OrderId) -> Option<pallet_nft::OrderStatus> {
+			NftModule::order_status(order_id)
+		}
+
+		fn all_nft_ids() -> Vec<NftId> {
+			NftModule::all_nft_ids()
+		}
+
+		fn nft_ids_paged(start_key: Option<NftId>, limit: u32) -> (Vec<NftId>, Option<NftId>) {
+			NftModule::nft_ids_paged(start_key, limit)
+		}
+	}
+
 	#[cfg(feature = "runtime-benchmarks")]
 	impl frame_benchmarking::Benchmark<Block> for Runtime {
 		fn dispatch_benchmark(